@@ -0,0 +1,272 @@
+use crate::display::{format_memory_size, format_percent};
+use crate::types::{AlertComparison, AlertEvent, AlertMetric, AlertRule, Snapshot};
+use std::collections::HashMap;
+
+// Threshold alerts: rules are checked against every snapshot and fire once the
+// condition has held for the rule's duration. A rule fires again only after
+// the value went back to normal in between.
+
+// Declare Constants
+const GIB: f64 = 1_073_741_824.0;
+
+impl AlertMetric {
+    // Stored in the AlertRules table
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertMetric::CpuUsage => "cpu_usage",
+            AlertMetric::MemoryUsage => "memory_usage",
+            AlertMetric::MemoryAvailable => "memory_available",
+            AlertMetric::SwapUsage => "swap_usage",
+            AlertMetric::DiskUsage => "disk_usage",
+        }
+    }
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "cpu_usage" => Some(AlertMetric::CpuUsage),
+            "memory_usage" => Some(AlertMetric::MemoryUsage),
+            "memory_available" => Some(AlertMetric::MemoryAvailable),
+            "swap_usage" => Some(AlertMetric::SwapUsage),
+            "disk_usage" => Some(AlertMetric::DiskUsage),
+            _ => None,
+        }
+    }
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlertMetric::CpuUsage => "CPU usage",
+            AlertMetric::MemoryUsage => "Memory usage",
+            AlertMetric::MemoryAvailable => "Available memory",
+            AlertMetric::SwapUsage => "Swap usage",
+            AlertMetric::DiskUsage => "Disk usage",
+        }
+    }
+    pub fn format_value(&self, value: f64) -> String {
+        match self {
+            AlertMetric::MemoryAvailable => format_memory_size(value.max(0.0) as u64),
+            _ => format_percent(value as f32),
+        }
+    }
+    // Current values by item, None when the snapshot didn't read the subsystem
+    fn values(&self, snapshot: &Snapshot) -> Option<Vec<(String, f64)>> {
+        let single = |value: f64| vec![(String::new(), value)];
+        match self {
+            AlertMetric::CpuUsage => snapshot
+                .cpu
+                .as_ref()
+                .map(|cpu| single(f64::from(cpu.usage_percent))),
+            AlertMetric::MemoryUsage => snapshot
+                .memory
+                .as_ref()
+                .map(|memory| single(percent(memory.used_bytes, memory.total_bytes))),
+            AlertMetric::MemoryAvailable => snapshot
+                .memory
+                .as_ref()
+                .map(|memory| single(memory.free_bytes as f64)),
+            AlertMetric::SwapUsage => snapshot
+                .memory
+                .as_ref()
+                .map(|memory| single(percent(memory.swap_used_bytes, memory.swap_total_bytes))),
+            AlertMetric::DiskUsage => snapshot.storage.as_ref().map(|storage| {
+                storage
+                    .iter()
+                    .map(|disk| {
+                        (
+                            disk.mount_point.clone().unwrap_or_default(),
+                            f64::from(disk.percent_used),
+                        )
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+impl AlertComparison {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertComparison::Above => "above",
+            AlertComparison::Below => "below",
+        }
+    }
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "above" => Some(AlertComparison::Above),
+            "below" => Some(AlertComparison::Below),
+            _ => None,
+        }
+    }
+}
+
+impl AlertRule {
+    // Rules a new database starts with
+    pub fn defaults() -> Vec<AlertRule> {
+        vec![
+            AlertRule {
+                name: "High CPU usage".to_string(),
+                metric: AlertMetric::CpuUsage,
+                comparison: AlertComparison::Above,
+                threshold: 90.0,
+                duration_secs: 30,
+                enabled: true,
+                ..Default::default()
+            },
+            AlertRule {
+                name: "Low memory".to_string(),
+                metric: AlertMetric::MemoryAvailable,
+                comparison: AlertComparison::Below,
+                threshold: GIB,
+                duration_secs: 0,
+                enabled: true,
+                ..Default::default()
+            },
+            AlertRule {
+                name: "Disk almost full".to_string(),
+                metric: AlertMetric::DiskUsage,
+                comparison: AlertComparison::Above,
+                threshold: 95.0,
+                duration_secs: 0,
+                enabled: true,
+                ..Default::default()
+            },
+        ]
+    }
+    pub fn is_breached(&self, value: f64) -> bool {
+        match self.comparison {
+            AlertComparison::Above => value > self.threshold,
+            AlertComparison::Below => value < self.threshold,
+        }
+    }
+    // e.g. "CPU usage above 90.00 % for 30 s"
+    pub fn describe(&self) -> String {
+        let mut description = format!(
+            "{} {} {}",
+            self.metric.label(),
+            self.comparison.as_str(),
+            self.metric.format_value(self.threshold)
+        );
+        if self.duration_secs > 0 {
+            description.push_str(&format!(" for {} s", self.duration_secs));
+        }
+        description
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Breach {
+    since: i64,
+    fired: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct AlertEvaluator {
+    rules: Vec<AlertRule>,
+    breaches: HashMap<(i64, String), Breach>, // By rule id and item
+}
+
+impl AlertEvaluator {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        AlertEvaluator {
+            rules,
+            breaches: HashMap::new(),
+        }
+    }
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+    // Replaces the rules, conditions that were building up start over
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>) {
+        self.rules = rules;
+        self.breaches.clear();
+    }
+
+    // Checks every enabled rule against the snapshot and returns the rules that
+    // fired. Subsystems missing from the snapshot keep their state until the
+    // next snapshot that has them.
+    pub fn evaluate(&mut self, snapshot: &Snapshot) -> Vec<AlertEvent> {
+        let now = snapshot.timestamp;
+        let mut events = Vec::new();
+        for rule in self.rules.iter().filter(|rule| rule.enabled) {
+            let Some(values) = rule.metric.values(snapshot) else {
+                continue;
+            };
+            // Forget disks that are no longer mounted
+            self.breaches.retain(|(id, item), _| {
+                *id != rule.id || values.iter().any(|(current, _)| current == item)
+            });
+            for (item, value) in values {
+                let key = (rule.id, item);
+                if !rule.is_breached(value) {
+                    self.breaches.remove(&key);
+                    continue;
+                }
+                let breach = self.breaches.entry(key.clone()).or_insert(Breach {
+                    since: now,
+                    fired: false,
+                });
+                if !breach.fired && now - breach.since >= i64::from(rule.duration_secs) {
+                    breach.fired = true;
+                    events.push(AlertEvent {
+                        timestamp: now,
+                        rule_id: rule.id,
+                        rule_name: rule.name.clone(),
+                        message: message(rule, &key.1, value),
+                        item: key.1,
+                        value,
+                    });
+                }
+            }
+        }
+        events
+    }
+}
+
+// e.g. "Disk almost full: Disk usage of / is 97.00 % (above 95.00 %)"
+fn message(rule: &AlertRule, item: &str, value: f64) -> String {
+    let subject = if item.is_empty() {
+        rule.metric.label().to_string()
+    } else {
+        format!("{} of {}", rule.metric.label(), item)
+    };
+    let mut message = format!(
+        "{}: {} is {} ({} {}",
+        rule.name,
+        subject,
+        rule.metric.format_value(value),
+        rule.comparison.as_str(),
+        rule.metric.format_value(rule.threshold)
+    );
+    if rule.duration_secs > 0 {
+        message.push_str(&format!(" for {} s", rule.duration_secs));
+    }
+    message.push(')');
+    message
+}
+
+fn percent(used: u64, total: u64) -> f64 {
+    if total > 0 {
+        used as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+// Shows the event as a freedesktop notification. Talking to D-Bus can block,
+// so it happens on its own thread and failures are only logged.
+#[cfg(target_os = "linux")]
+pub fn notify_desktop(event: &AlertEvent) {
+    let summary = event.rule_name.clone();
+    let body = event.message.clone();
+    std::thread::spawn(move || {
+        let result = notify_rust::Notification::new()
+            .appname("MachineInfo")
+            .summary(&summary)
+            .body(&body)
+            .show();
+        if let Err(e) = result {
+            eprintln!("Error: Unable to show notification: {}", e);
+        }
+    });
+}
+
+// Desktop notifications are only implemented for Linux, the banner still shows
+#[cfg(not(target_os = "linux"))]
+pub fn notify_desktop(_event: &AlertEvent) {}
@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// Ring buffer of percentage samples for the live charts.
+// The buffer keeps samples by age, long enough for the longest range at any
+// refresh interval; shorter ranges only draw the newest part of it.
+
+// Declare Constants
+pub const CHART_VIEWBOX: f32 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChartRange {
+    #[default]
+    Minute,
+    FiveMinutes,
+    Hour,
+}
+
+impl ChartRange {
+    // Matches the order of the range selector in the UI
+    pub fn from_index(index: i32) -> Self {
+        match index {
+            1 => ChartRange::FiveMinutes,
+            2 => ChartRange::Hour,
+            _ => ChartRange::Minute,
+        }
+    }
+    pub fn duration(&self) -> Duration {
+        match self {
+            ChartRange::Minute => Duration::from_secs(60),
+            ChartRange::FiveMinutes => Duration::from_secs(5 * 60),
+            ChartRange::Hour => Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChartBuffer {
+    samples: VecDeque<(Instant, f32)>,
+    max_age: Duration, // Samples older than this, relative to the newest one, are dropped
+}
+
+impl ChartBuffer {
+    pub fn new(max_age: Duration) -> Self {
+        ChartBuffer {
+            samples: VecDeque::new(),
+            max_age,
+        }
+    }
+    pub fn for_longest_range() -> Self {
+        Self::new(ChartRange::Hour.duration())
+    }
+    pub fn push(&mut self, value: f32) {
+        self.push_at(Instant::now(), value);
+    }
+    pub fn push_at(&mut self, at: Instant, value: f32) {
+        self.samples.push_back((at, value));
+        while self
+            .samples
+            .front()
+            .is_some_and(|(oldest, _)| at.saturating_duration_since(*oldest) > self.max_age)
+        {
+            self.samples.pop_front();
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+    pub fn latest(&self) -> Option<f32> {
+        self.samples.back().map(|(_, value)| *value)
+    }
+
+    // Builds SVG path commands for a Slint `Path` with a 100 x 100 viewbox.
+    // Values are percentages, the newest sample sits on the right edge.
+    pub fn to_path_commands(&self, range: ChartRange, now: Instant) -> String {
+        let window = range.duration().as_secs_f32();
+        let mut commands = String::new();
+        for (at, value) in self.samples.iter() {
+            let age = now.saturating_duration_since(*at).as_secs_f32();
+            if age > window {
+                continue;
+            }
+            let x = CHART_VIEWBOX - age / window * CHART_VIEWBOX;
+            let y = CHART_VIEWBOX - value.clamp(0.0, 100.0) / 100.0 * CHART_VIEWBOX;
+            let verb = if commands.is_empty() { 'M' } else { 'L' };
+            commands.push_str(&format!("{verb} {x:.2} {y:.2} "));
+        }
+        commands.trim_end().to_string()
+    }
+}
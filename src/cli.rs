@@ -0,0 +1,587 @@
+#[cfg(feature = "serde")]
+use crate::diff;
+use crate::display::{
+    FanDisplay, FeaturesDisplay, LoadDisplay, MemoryDisplay, NetworkDisplay, ProcessorDisplay,
+    StorageDisplay, SystemDisplay, TemperatureDisplay, TimesDisplay, TopologyDisplay,
+    format_frequency, format_percent,
+};
+use crate::error::{self, MachineInfoError};
+#[cfg(feature = "serde")]
+use crate::export::{read_profile, render_json_export};
+use crate::exporter;
+use crate::sys::collector::Collector;
+use crate::sys::provider::MetricsProvider;
+use crate::types::{
+    AlertComparison, AlertMetric, AlertRule, CpuFeatures, MachineIdentity, MachineProfile,
+    Snapshot, Subsystems, WindowInformation,
+};
+use rusqlite::Connection;
+use std::error::Error;
+use std::net::TcpListener;
+#[cfg(feature = "serde")]
+use std::path::Path;
+use std::process::ExitCode;
+use std::thread;
+
+// Headless entry point: prints the same readings as the window without opening one.
+
+pub const USAGE: &str = "Usage: machine_info [OPTIONS]
+       machine_info snapshot > FILE
+       machine_info diff [--json] FILE [FILE]
+       machine_info alerts [add NAME METRIC above|below THRESHOLD [SECONDS]]
+       machine_info alerts set ID THRESHOLD [SECONDS]
+       machine_info alerts enable|disable|delete ID
+
+Without options the graphical interface is started.
+
+Commands:
+  snapshot     Print a machine profile as JSON, the same file File → Export
+               Snapshot writes
+  diff         Compare two machine profiles, or one profile with this machine
+  alerts       List the alert rules, or add, change, enable, disable or delete
+               one. METRIC is cpu_usage, memory_usage, memory_available,
+               swap_usage or disk_usage; THRESHOLD is in percent, or in bytes
+               for memory_available. The window reads the rules at startup
+
+Options:
+  --json       Print the readings as JSON
+  --cpu        Print processor information, load, temperatures and fan speeds
+  --memory     Print memory information
+  --storage    Print information for every mounted disk
+  --network    Print information and throughput for every network interface
+  --system     Print operating system, uptime and logged-in users
+  --cpu-flags[=TEXT]
+               Print the processor's instruction-set flags, only those
+               containing TEXT when given. Fails when none match
+  --serve[=ADDR]
+               Serve the readings as OpenMetrics at http://ADDR/metrics
+               instead of printing them (default 127.0.0.1:9184)
+  -h, --help   Print this help";
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CliOptions {
+    pub json: bool,
+    pub cpu: bool,
+    pub memory: bool,
+    pub storage: bool,
+    pub network: bool,
+    pub system: bool,
+    pub serve: Option<String>,     // Address to export metrics on
+    pub cpu_flags: Option<String>, // Search text, empty for every flag
+    pub command: Option<CliCommand>,
+    pub help: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Snapshot,
+    Diff(Vec<String>), // One or two profile files
+    Alerts(AlertsCommand),
+}
+
+// What `alerts` does to the AlertRules table
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertsCommand {
+    List,
+    Add(AlertRule),
+    Set {
+        id: i64,
+        threshold: f64,
+        duration_secs: Option<u32>, // Kept as it is when not given
+    },
+    Enable(i64, bool), // Rule id, false to disable
+    Delete(i64),
+}
+
+impl AlertsCommand {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let id = |value: &str| {
+            value
+                .parse::<i64>()
+                .map_err(|_| format!("'{}' is not a rule id", value))
+        };
+        let threshold = |value: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("'{}' is not a threshold", value))
+        };
+        let seconds = |value: Option<&&str>| {
+            value
+                .map(|value| {
+                    value
+                        .parse::<u32>()
+                        .map_err(|_| format!("'{}' is not a number of seconds", value))
+                })
+                .transpose()
+        };
+        match args.as_slice() {
+            [] => Ok(AlertsCommand::List),
+            ["add", name, metric, comparison, value, rest @ ..] if rest.len() <= 1 => {
+                Ok(AlertsCommand::Add(AlertRule {
+                    name: name.to_string(),
+                    metric: AlertMetric::parse(metric)
+                        .ok_or_else(|| format!("Unknown metric '{}'", metric))?,
+                    comparison: AlertComparison::parse(comparison)
+                        .ok_or_else(|| format!("Expected above or below, not '{}'", comparison))?,
+                    threshold: threshold(value)?,
+                    duration_secs: seconds(rest.first())?.unwrap_or_default(),
+                    enabled: true,
+                    ..Default::default()
+                }))
+            }
+            ["set", rule, value, rest @ ..] if rest.len() <= 1 => Ok(AlertsCommand::Set {
+                id: id(rule)?,
+                threshold: threshold(value)?,
+                duration_secs: seconds(rest.first())?,
+            }),
+            ["enable", rule] => Ok(AlertsCommand::Enable(id(rule)?, true)),
+            ["disable", rule] => Ok(AlertsCommand::Enable(id(rule)?, false)),
+            ["delete", rule] => Ok(AlertsCommand::Delete(id(rule)?)),
+            _ => Err("Unknown alerts command".to_string()),
+        }
+    }
+}
+
+impl CliOptions {
+    // Returns None when no headless option was given and the UI should start
+    pub fn parse<I, S>(args: I) -> Result<Option<CliOptions>, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut options = CliOptions::default();
+        let mut headless = false;
+        let mut alert_args = Vec::new();
+        for (index, arg) in args.into_iter().enumerate() {
+            match arg.as_ref() {
+                "snapshot" if index == 0 => options.command = Some(CliCommand::Snapshot),
+                "diff" if index == 0 => options.command = Some(CliCommand::Diff(Vec::new())),
+                "alerts" if index == 0 => {
+                    options.command = Some(CliCommand::Alerts(AlertsCommand::List))
+                }
+                other if !other.starts_with('-') => match options.command.as_mut() {
+                    Some(CliCommand::Diff(files)) => files.push(other.to_string()),
+                    Some(CliCommand::Alerts(_)) => alert_args.push(other.to_string()),
+                    _ => return Err(format!("Unexpected argument '{}'\n\n{}", other, USAGE)),
+                },
+                "--json" => options.json = true,
+                "--cpu" => options.cpu = true,
+                "--memory" => options.memory = true,
+                "--storage" => options.storage = true,
+                "--network" => options.network = true,
+                "--system" => options.system = true,
+                "--serve" => options.serve = Some(exporter::DEFAULT_ADDRESS.to_string()),
+                "--cpu-flags" => options.cpu_flags = Some(String::new()),
+                other if other.starts_with("--cpu-flags=") => {
+                    options.cpu_flags = Some(other["--cpu-flags=".len()..].to_string())
+                }
+                other if other.starts_with("--serve=") => {
+                    options.serve = Some(other["--serve=".len()..].to_string())
+                }
+                "-h" | "--help" => options.help = true,
+                // macOS passes a process serial number when launched from Finder
+                other if other.starts_with("-psn_") => continue,
+                other => return Err(format!("Unknown option '{}'\n\n{}", other, USAGE)),
+            }
+            headless = true;
+        }
+        if let Some(CliCommand::Diff(files)) = &options.command
+            && !(1..=2).contains(&files.len())
+            && !options.help
+        {
+            return Err(format!("diff takes one or two files\n\n{}", USAGE));
+        }
+        if let Some(CliCommand::Alerts(command)) = &mut options.command {
+            *command = AlertsCommand::parse(&alert_args)
+                .map_err(|message| format!("{}\n\n{}", message, USAGE))?;
+        }
+        // No section selected means all of them
+        if !options.cpu
+            && !options.memory
+            && !options.storage
+            && !options.network
+            && !options.system
+        {
+            options.cpu = true;
+            options.memory = true;
+            options.storage = true;
+            options.network = true;
+            options.system = true;
+        }
+        Ok(headless.then_some(options))
+    }
+    pub fn subsystems(&self) -> Subsystems {
+        Subsystems {
+            cpu: self.cpu,
+            memory: self.memory,
+            storage: self.storage,
+            network: self.network,
+            processes: false,
+            system: self.system,
+            // Temperatures are part of the CPU section, like on the CPU page
+            sensors: self.cpu,
+        }
+    }
+}
+
+// Release builds on Windows use the GUI subsystem so no console window flashes up
+// behind the app, which also leaves them without stdout. Borrow the console of the
+// shell that started us; output redirected to a file or pipe is unaffected.
+#[cfg(windows)]
+pub fn attach_parent_console() {
+    use windows_sys::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+    // SAFETY: takes no pointers, fails harmlessly when there already is a console
+    // or the parent has none
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+#[cfg(not(windows))]
+pub fn attach_parent_console() {}
+
+// Runs the command-line mode. Failure without an error means "nothing matched",
+// which scripts check through the exit status.
+pub fn run(options: &CliOptions) -> Result<ExitCode, Box<dyn Error>> {
+    if options.help {
+        println!("{}", USAGE);
+        return Ok(ExitCode::SUCCESS);
+    }
+    // Profiles are JSON files, so the commands need serde as much as --json does
+    let needs_json = matches!(
+        options.command,
+        Some(CliCommand::Snapshot | CliCommand::Diff(_))
+    );
+    if !cfg!(feature = "serde") && (options.json || needs_json) {
+        return Err("JSON needs a build with the serde feature".into());
+    }
+    match &options.command {
+        #[cfg(feature = "serde")]
+        Some(CliCommand::Snapshot) => {
+            let profile = collect_profile()?;
+            println!(
+                "{}",
+                render_json_export(&profile.identity, &profile.snapshot)
+            );
+            return Ok(ExitCode::SUCCESS);
+        }
+        #[cfg(feature = "serde")]
+        Some(CliCommand::Diff(files)) => {
+            let left = read_profile(Path::new(&files[0]))?;
+            let (right, right_name) = match files.get(1) {
+                Some(file) => (read_profile(Path::new(file))?, file.as_str()),
+                None => (collect_profile()?, "this machine"),
+            };
+            let differences = diff::diff_profiles(&left, &right);
+            if options.json {
+                println!("{}", diff::render_json(&differences));
+            } else {
+                print!("{}", diff::render_text(&files[0], right_name, &differences));
+            }
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(CliCommand::Alerts(command)) => {
+            // Same database the window keeps its settings and alert history in
+            let conn = WindowInformation::connect_to_db()?;
+            AlertRule::create_table(&conn)?;
+            print!("{}", run_alerts(&conn, command)?);
+            return Ok(ExitCode::SUCCESS);
+        }
+        _ => {}
+    }
+    if let Some(query) = &options.cpu_flags {
+        let features = CpuFeatures::read()?;
+        let flags = features.search_flags(query);
+        // Exit status only, so scripts can test for a flag without parsing output
+        if flags.is_empty() {
+            eprintln!("No CPU flag contains '{}'", query);
+            return Ok(ExitCode::FAILURE);
+        }
+        #[cfg(feature = "serde")]
+        if options.json {
+            println!("{}", serde_json::to_string_pretty(&flags)?);
+            return Ok(ExitCode::SUCCESS);
+        }
+        println!("{}", flags.join("\n"));
+        return Ok(ExitCode::SUCCESS);
+    }
+    if let Some(address) = &options.serve {
+        let listener = TcpListener::bind(address)?;
+        eprintln!(
+            "Serving metrics on http://{}/metrics",
+            listener.local_addr()?
+        );
+        exporter::serve(listener, &mut Collector::new(), options.subsystems())?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let report = collect(options)?;
+    #[cfg(feature = "serde")]
+    if options.json {
+        println!("{}", render_json(&report));
+        return Ok(ExitCode::SUCCESS);
+    }
+    print!("{}", render_text(&report));
+    Ok(ExitCode::SUCCESS)
+}
+
+// Applies `command` to the stored rules and returns what to print: every rule for
+// `list`, the rule that changed otherwise
+pub fn run_alerts(conn: &Connection, command: &AlertsCommand) -> error::Result<String> {
+    let rules = AlertRule::load_all(conn)?;
+    let find = |id: i64| {
+        rules
+            .iter()
+            .find(|rule| rule.id == id)
+            .cloned()
+            .ok_or_else(|| MachineInfoError::NoData(format!("alert rule {}", id)))
+    };
+    let changed = match command {
+        AlertsCommand::List => return Ok(rules.iter().map(render_alert_rule).collect()),
+        AlertsCommand::Add(rule) => {
+            let id = rule.save_to_db(conn)?;
+            AlertRule { id, ..rule.clone() }
+        }
+        AlertsCommand::Set {
+            id,
+            threshold,
+            duration_secs,
+        } => {
+            let mut rule = find(*id)?;
+            rule.threshold = *threshold;
+            rule.duration_secs = duration_secs.unwrap_or(rule.duration_secs);
+            rule.save_to_db(conn)?;
+            rule
+        }
+        AlertsCommand::Enable(id, enabled) => {
+            let mut rule = find(*id)?;
+            rule.enabled = *enabled;
+            rule.save_to_db(conn)?;
+            rule
+        }
+        AlertsCommand::Delete(id) => {
+            let rule = find(*id)?;
+            AlertRule::delete_from_db(conn, *id)?;
+            return Ok(format!("Deleted rule {}: {}\n", rule.id, rule.name));
+        }
+    };
+    Ok(render_alert_rule(&changed))
+}
+
+// e.g. "  1  on   High CPU usage: CPU usage above 90.00 % for 30 s"
+fn render_alert_rule(rule: &AlertRule) -> String {
+    format!(
+        "{:>3}  {:<3}  {}: {}\n",
+        rule.id,
+        if rule.enabled { "on" } else { "off" },
+        rule.name,
+        rule.describe()
+    )
+}
+
+// Collects only what was asked for from the live machine
+pub fn collect(options: &CliOptions) -> error::Result<Snapshot> {
+    let mut collector = Collector::new();
+    // CPU usage, network and swap rates are computed between two refreshes,
+    // so give sysinfo one interval before reading them
+    if options.cpu || options.network || options.memory {
+        thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    }
+    collect_from(&mut collector, options)
+}
+
+// Profile of the live machine, what `snapshot` prints and `diff` compares against
+pub fn collect_profile() -> error::Result<MachineProfile> {
+    let options = CliOptions {
+        cpu: true,
+        memory: true,
+        storage: true,
+        ..CliOptions::default()
+    };
+    Ok(MachineProfile {
+        identity: MachineIdentity::read(),
+        snapshot: collect(&options)?,
+    })
+}
+
+// Collects only what was asked for from any provider, failing on the first section that can't be read
+pub fn collect_from<P: MetricsProvider>(
+    collector: &mut Collector<P>,
+    options: &CliOptions,
+) -> error::Result<Snapshot> {
+    let (snapshot, errors) = collector.refresh(options.subsystems());
+    match errors.into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(snapshot),
+    }
+}
+
+// Values keep their raw units (bytes, percent, MHz) so scripts never have to parse "GB" strings
+#[cfg(feature = "serde")]
+pub fn render_json(report: &Snapshot) -> String {
+    serde_json::to_string_pretty(report).expect("snapshots always serialize")
+}
+
+pub fn render_text(report: &Snapshot) -> String {
+    let mut out = String::new();
+    if let Some(system) = &report.system {
+        let display = SystemDisplay::from(system);
+        out.push_str("System Information\n");
+        out.push_str(&format!("  Hostname:     {}\n", display.hostname));
+        out.push_str(&format!("  OS:           {}\n", display.os_name));
+        out.push_str(&format!("  OS Version:   {}\n", display.os_version));
+        out.push_str(&format!("  Kernel:       {}\n", display.kernel_version));
+        out.push_str(&format!("  Distribution: {}\n", display.distribution_id));
+        out.push_str(&format!("  Architecture: {}\n", display.architecture));
+        out.push_str(&format!("  Uptime:       {}\n", display.uptime));
+        out.push_str(&format!("  Boot Time:    {}\n", display.boot_time));
+        for user in &display.users {
+            let mut session = format!("  User:         {} on {}", user.name, user.terminal);
+            if !user.host.is_empty() {
+                session.push_str(&format!(" from {}", user.host));
+            }
+            out.push_str(&format!("{} since {}\n", session, user.login_time));
+        }
+        out.push('\n');
+    }
+    if let Some(cpu) = &report.cpu {
+        let display = ProcessorDisplay::from(cpu);
+        out.push_str("CPU Information\n");
+        out.push_str(&format!("  CPU-ID:         {}\n", display.name));
+        out.push_str(&format!("  CPU Vendor:     {}\n", display.vendor));
+        out.push_str(&format!("  Speed:          {}\n", display.speed));
+        out.push_str(&format!("  Logical CPUs:   {}\n", display.cores));
+        out.push_str(&format!("  Usage:          {}\n", display.usage));
+        if let Some(times) = &cpu.times {
+            let times = TimesDisplay::from(times);
+            out.push_str(&format!("  CPU Time:       {}\n", times.summary()));
+        }
+        if let Some(load) = &cpu.load {
+            let load = LoadDisplay::new(load, cpu.cores);
+            out.push_str(&format!("  Load Average:   {}\n", load.averages));
+            out.push_str(&format!("  Load per CPU:   {}\n", load.per_cpu));
+            out.push_str(&format!("  Run Queue:      {}\n", load.run_queue));
+            out.push_str(&format!("  Ctx Switches:   {}\n", load.context_switches));
+            out.push_str(&format!("  Interrupts:     {}\n", load.interrupts));
+        }
+        out.push_str(&format!("  Architecture:   {}\n", display.architecture));
+        if let Some(features) = &cpu.features {
+            let features = FeaturesDisplay::from(features);
+            if !features.microcode.is_empty() {
+                out.push_str(&format!("  Microcode:      {}\n", features.microcode));
+            }
+            if !features.level.is_empty() {
+                out.push_str(&format!("  ISA Level:      {}\n", features.level));
+            }
+            out.push_str(&format!("  Flags:          {}\n", features.flags));
+        }
+        if let Some(topology) = &cpu.topology {
+            let topology = TopologyDisplay::from(topology);
+            out.push_str(&format!("  Sockets:        {}\n", topology.sockets));
+            out.push_str(&format!("  Physical Cores: {}\n", topology.physical_cores));
+            out.push_str(&format!(
+                "  Threads/Core:   {}\n",
+                topology.threads_per_core
+            ));
+            for (label, size) in &topology.caches {
+                out.push_str(&format!("  {:<15} {}\n", format!("{}:", label), size));
+            }
+            for (label, cpus) in &topology.numa_nodes {
+                out.push_str(&format!("  {:<15} CPUs {}\n", format!("{}:", label), cpus));
+            }
+        }
+        for core in &cpu.per_core {
+            out.push_str(&format!(
+                "  CPU {:<3}        {:>8}  {}",
+                core.index,
+                format_percent(core.usage_percent),
+                format_frequency(core.frequency_mhz)
+            ));
+            if let Some(times) = &core.times {
+                out.push_str(&format!("  {}", TimesDisplay::from(times).summary()));
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    if let Some(sensors) = &report.sensors
+        && (!sensors.temperatures.is_empty() || !sensors.fans.is_empty())
+    {
+        let temperatures: Vec<TemperatureDisplay> = sensors
+            .temperatures
+            .iter()
+            .map(TemperatureDisplay::from)
+            .collect();
+        let fans: Vec<FanDisplay> = sensors.fans.iter().map(FanDisplay::from).collect();
+        let width = temperatures
+            .iter()
+            .map(|sensor| sensor.label.len())
+            .chain(fans.iter().map(|fan| fan.label.len()))
+            .max()
+            .unwrap_or_default()
+            + 1;
+        out.push_str("Sensors\n");
+        for sensor in &temperatures {
+            let label = format!("{}:", sensor.label);
+            out.push_str(&format!("  {:<width$} {}\n", label, sensor.summary()));
+        }
+        for fan in &fans {
+            let label = format!("{}:", fan.label);
+            out.push_str(&format!("  {:<width$} {}\n", label, fan.speed));
+        }
+        out.push('\n');
+    }
+    if let Some(memory) = &report.memory {
+        let display = MemoryDisplay::from(memory);
+        out.push_str("Memory Information\n");
+        out.push_str(&format!("  Total Memory: {}\n", display.total));
+        out.push_str(&format!("  Used Memory:  {}\n", display.used));
+        out.push_str(&format!("  Free Memory:  {}\n", display.free));
+        out.push_str(&format!("  Total Swap:   {}\n", display.swap_total));
+        out.push_str(&format!("  Used Swap:    {}\n", display.swap_used));
+        out.push_str(&format!("  Free Swap:    {}\n", display.swap_free));
+        out.push_str(&format!("  Swap In:      {}\n", display.swap_in));
+        out.push_str(&format!("  Swap Out:     {}\n", display.swap_out));
+        out.push_str(&format!("  Buffers:      {}\n", display.buffers));
+        out.push_str(&format!("  Cached:       {}\n", display.cached));
+        out.push_str(&format!("  Shared:       {}\n", display.shared));
+        out.push_str(&format!("  Slab (recl.): {}\n", display.slab_reclaimable));
+        out.push_str(&format!("  Slab (unrec): {}\n", display.slab_unreclaimable));
+        out.push_str(&format!("  Dirty:        {}\n", display.dirty));
+        out.push_str(&format!("  Writeback:    {}\n", display.writeback));
+        out.push('\n');
+    }
+    if let Some(storage) = &report.storage {
+        out.push_str("Storage Information\n");
+        for disk in storage {
+            let display = StorageDisplay::from(disk);
+            out.push_str(&format!("  {}\n", display.mount_point));
+            out.push_str(&format!("    Drive Name:    {}\n", display.name));
+            out.push_str(&format!("    File System:   {}\n", display.file_system));
+            out.push_str(&format!("    Type:          {}\n", display.type_));
+            out.push_str(&format!("    Total Storage: {}\n", display.total_space));
+            out.push_str(&format!("    Used Storage:  {}\n", display.used_space));
+            out.push_str(&format!("    Free Storage:  {}\n", display.free_space));
+            out.push_str(&format!("    Percent Used:  {}\n", display.percent_used));
+        }
+        out.push('\n');
+    }
+    if let Some(network) = &report.network {
+        out.push_str("Network Information\n");
+        for interface in network {
+            let display = NetworkDisplay::from(interface);
+            out.push_str(&format!("  {}\n", display.name));
+            out.push_str(&format!("    State:       {}\n", display.link_state));
+            out.push_str(&format!("    MAC Address: {}\n", display.mac_address));
+            out.push_str(&format!("    IPv4:        {}\n", display.ipv4_addresses));
+            out.push_str(&format!("    IPv6:        {}\n", display.ipv6_addresses));
+            out.push_str(&format!("    MTU:         {}\n", display.mtu));
+            out.push_str(&format!("    Receiving:   {}\n", display.rx_rate));
+            out.push_str(&format!("    Sending:     {}\n", display.tx_rate));
+            out.push_str(&format!("    Packets:     {}\n", display.packets));
+            out.push_str(&format!("    Errors:      {}\n", display.errors));
+        }
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,150 @@
+use crate::types::{AlertComparison, AlertEvent, AlertMetric, AlertRule};
+use rusqlite::{Connection, Result as SqliteResult, params};
+
+impl AlertRule {
+    pub fn create_table(conn: &Connection) -> SqliteResult<()> {
+        // Seed the default rules only when the table is new, so deleted rules stay deleted
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'AlertRules')",
+            [],
+            |row| row.get(0),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS AlertRules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            metric TEXT NOT NULL,
+            comparison TEXT NOT NULL,
+            threshold REAL NOT NULL,
+            duration INTEGER NOT NULL,
+            enabled INTEGER NOT NULL,
+            modified_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+            [],
+        )?;
+        if !exists {
+            for rule in AlertRule::defaults() {
+                rule.save_to_db(conn)?;
+            }
+        }
+        Ok(())
+    }
+    // Inserts the rule when its id is 0, updates it otherwise. Returns the id.
+    pub fn save_to_db(&self, conn: &Connection) -> SqliteResult<i64> {
+        if self.id == 0 {
+            conn.execute(
+                "INSERT INTO AlertRules (name, metric, comparison, threshold, duration, enabled)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    self.name,
+                    self.metric.as_str(),
+                    self.comparison.as_str(),
+                    self.threshold,
+                    self.duration_secs,
+                    self.enabled
+                ],
+            )?;
+            return Ok(conn.last_insert_rowid());
+        }
+        conn.execute(
+            "UPDATE AlertRules SET name = ?2, metric = ?3, comparison = ?4, threshold = ?5,
+                 duration = ?6, enabled = ?7, modified_at = CURRENT_TIMESTAMP
+             WHERE id = ?1",
+            params![
+                self.id,
+                self.name,
+                self.metric.as_str(),
+                self.comparison.as_str(),
+                self.threshold,
+                self.duration_secs,
+                self.enabled
+            ],
+        )?;
+        Ok(self.id)
+    }
+    pub fn delete_from_db(conn: &Connection, id: i64) -> SqliteResult<()> {
+        conn.execute("DELETE FROM AlertRules WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+    // Rules with a metric or comparison this version doesn't know are skipped
+    pub fn load_all(conn: &Connection) -> SqliteResult<Vec<AlertRule>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, name, metric, comparison, threshold, duration, enabled FROM AlertRules ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let rule = AlertRule {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                threshold: row.get(4)?,
+                duration_secs: row.get(5)?,
+                enabled: row.get(6)?,
+                ..Default::default()
+            };
+            let metric: String = row.get(2)?;
+            let comparison: String = row.get(3)?;
+            Ok((rule, metric, comparison))
+        })?;
+        let mut rules = Vec::new();
+        for row in rows {
+            let (mut rule, metric, comparison) = row?;
+            if let (Some(metric), Some(comparison)) = (
+                AlertMetric::parse(&metric),
+                AlertComparison::parse(&comparison),
+            ) {
+                rule.metric = metric;
+                rule.comparison = comparison;
+                rules.push(rule);
+            }
+        }
+        Ok(rules)
+    }
+}
+
+impl AlertEvent {
+    pub fn create_table(conn: &Connection) -> SqliteResult<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS AlertHistory (
+            timestamp INTEGER NOT NULL,
+            rule_id INTEGER NOT NULL,
+            rule_name TEXT NOT NULL,
+            item TEXT NOT NULL,
+            value REAL NOT NULL,
+            message TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS AlertHistoryTime ON AlertHistory (timestamp);",
+        )
+    }
+    pub fn save_to_db(&self, conn: &Connection) -> SqliteResult<()> {
+        conn.execute(
+            "INSERT INTO AlertHistory (timestamp, rule_id, rule_name, item, value, message)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                self.timestamp,
+                self.rule_id,
+                self.rule_name,
+                self.item,
+                self.value,
+                self.message
+            ],
+        )?;
+        Ok(())
+    }
+    // Newest first
+    pub fn load_recent(conn: &Connection, limit: u32) -> SqliteResult<Vec<AlertEvent>> {
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, rule_id, rule_name, item, value, message FROM AlertHistory
+             ORDER BY timestamp DESC, rowid DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok(AlertEvent {
+                timestamp: row.get(0)?,
+                rule_id: row.get(1)?,
+                rule_name: row.get(2)?,
+                item: row.get(3)?,
+                value: row.get(4)?,
+                message: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
@@ -0,0 +1,400 @@
+use crate::types::{
+    CpuSample, HistoryRecorder, HistorySettings, Memory, MemorySample, Processor, SensorSample,
+    Storage, StorageSample, Temperature,
+};
+use rusqlite::{Connection, Result as SqliteResult, Row, params};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Declare Constants
+pub const RESOLUTION_RAW: u32 = 0;
+pub const RESOLUTION_MINUTE: u32 = 60;
+pub const RESOLUTION_HOUR: u32 = 3600;
+const COMPACTION_INTERVAL_SECS: i64 = 60;
+const HISTORY_TABLES: [&str; 4] = [
+    "CpuHistory",
+    "MemoryHistory",
+    "StorageHistory",
+    "SensorHistory",
+];
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        HistorySettings {
+            sample_interval_secs: 5,
+            raw_retention_secs: 6 * 3600,
+            minute_retention_secs: 7 * 86_400,
+            hour_retention_secs: 90 * 86_400,
+        }
+    }
+}
+
+impl HistorySettings {
+    pub fn create_table(conn: &Connection) -> SqliteResult<()> {
+        // Create the table only if it doesn't exist
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS HistorySettings (
+            id INTEGER PRIMARY KEY,
+            sample_interval INTEGER NOT NULL,
+            raw_retention INTEGER NOT NULL,
+            minute_retention INTEGER NOT NULL,
+            hour_retention INTEGER NOT NULL,
+            modified_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+            [],
+        )?;
+
+        // Insert default values ONLY if they don't exist (using INSERT OR IGNORE)
+        let defaults = HistorySettings::default();
+        conn.execute(
+            "INSERT OR IGNORE INTO HistorySettings (id, sample_interval, raw_retention, minute_retention, hour_retention)
+             VALUES (1, ?1, ?2, ?3, ?4)",
+            params![
+                defaults.sample_interval_secs,
+                defaults.raw_retention_secs,
+                defaults.minute_retention_secs,
+                defaults.hour_retention_secs
+            ],
+        )?;
+        Ok(())
+    }
+    pub fn save_to_db(&self, conn: &Connection) -> SqliteResult<()> {
+        conn.execute(
+            "INSERT INTO HistorySettings (id, sample_interval, raw_retention, minute_retention, hour_retention, modified_at)
+             VALUES (1, ?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+             ON CONFLICT(id) DO UPDATE SET
+                 sample_interval = excluded.sample_interval,
+                 raw_retention = excluded.raw_retention,
+                 minute_retention = excluded.minute_retention,
+                 hour_retention = excluded.hour_retention,
+                 modified_at = CURRENT_TIMESTAMP",
+            params![
+                self.sample_interval_secs,
+                self.raw_retention_secs,
+                self.minute_retention_secs,
+                self.hour_retention_secs
+            ],
+        )?;
+        Ok(())
+    }
+    pub fn load_from_db(conn: &Connection) -> SqliteResult<HistorySettings> {
+        conn.query_row(
+            "SELECT sample_interval, raw_retention, minute_retention, hour_retention FROM HistorySettings WHERE id = 1",
+            [],
+            |row| {
+                Ok(HistorySettings {
+                    sample_interval_secs: row.get(0)?,
+                    raw_retention_secs: row.get(1)?,
+                    minute_retention_secs: row.get(2)?,
+                    hour_retention_secs: row.get(3)?,
+                })
+            },
+        )
+    }
+}
+
+impl HistoryRecorder {
+    pub fn new(settings: HistorySettings) -> Self {
+        HistoryRecorder {
+            settings,
+            last_sample: None,
+            last_compaction: None,
+        }
+    }
+    // Creates the settings and time-series tables, then loads the stored settings
+    pub fn from_db(conn: &Connection) -> SqliteResult<Self> {
+        HistorySettings::create_table(conn)?;
+        Self::create_tables(conn)?;
+        Ok(Self::new(HistorySettings::load_from_db(conn)?))
+    }
+    pub fn create_tables(conn: &Connection) -> SqliteResult<()> {
+        // Every table keeps raw samples and aggregates side by side, told apart by resolution
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS CpuHistory (
+            timestamp INTEGER NOT NULL,
+            resolution INTEGER NOT NULL,
+            usage_avg REAL NOT NULL,
+            usage_max REAL NOT NULL,
+            frequency_mhz INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS CpuHistoryTime ON CpuHistory (resolution, timestamp);
+        CREATE TABLE IF NOT EXISTS MemoryHistory (
+            timestamp INTEGER NOT NULL,
+            resolution INTEGER NOT NULL,
+            used_avg INTEGER NOT NULL,
+            used_max INTEGER NOT NULL,
+            total INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS MemoryHistoryTime ON MemoryHistory (resolution, timestamp);
+        CREATE TABLE IF NOT EXISTS StorageHistory (
+            timestamp INTEGER NOT NULL,
+            resolution INTEGER NOT NULL,
+            mount_point TEXT NOT NULL,
+            used_avg INTEGER NOT NULL,
+            used_max INTEGER NOT NULL,
+            total INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS StorageHistoryTime ON StorageHistory (resolution, timestamp);
+        CREATE TABLE IF NOT EXISTS SensorHistory (
+            timestamp INTEGER NOT NULL,
+            resolution INTEGER NOT NULL,
+            sensor TEXT NOT NULL,
+            chip TEXT NOT NULL,
+            temperature_avg REAL NOT NULL,
+            temperature_max REAL NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS SensorHistoryTime ON SensorHistory (resolution, timestamp);",
+        )
+    }
+
+    // True once the sample interval has passed since the last recorded sample
+    pub fn is_due(&self, now: i64) -> bool {
+        self.last_sample
+            .is_none_or(|last| now - last >= i64::from(self.settings.sample_interval_secs))
+    }
+    // Records one sample per subsystem if the sample interval has elapsed.
+    // Returns true when a sample was written.
+    pub fn record(
+        &mut self,
+        conn: &Connection,
+        cpu: &Processor,
+        memory: &Memory,
+        storage: &[Storage],
+        temperatures: &[Temperature],
+    ) -> SqliteResult<bool> {
+        let now = unix_now();
+        if !self.is_due(now) {
+            return Ok(false);
+        }
+        self.record_at(conn, now, cpu, memory, storage, temperatures)?;
+        self.last_sample = Some(now);
+
+        // Downsampling is cheap but there is no reason to run it on every tick
+        if self
+            .last_compaction
+            .is_none_or(|last| now - last >= COMPACTION_INTERVAL_SECS)
+        {
+            self.compact(conn, now)?;
+            self.last_compaction = Some(now);
+        }
+        Ok(true)
+    }
+    pub fn record_at(
+        &self,
+        conn: &Connection,
+        timestamp: i64,
+        cpu: &Processor,
+        memory: &Memory,
+        storage: &[Storage],
+        temperatures: &[Temperature],
+    ) -> SqliteResult<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO CpuHistory (timestamp, resolution, usage_avg, usage_max, frequency_mhz)
+             VALUES (?1, ?2, ?3, ?3, ?4)",
+            params![
+                timestamp,
+                RESOLUTION_RAW,
+                cpu.usage_percent,
+                cpu.frequency_mhz as i64
+            ],
+        )?;
+        tx.execute(
+            "INSERT INTO MemoryHistory (timestamp, resolution, used_avg, used_max, total)
+             VALUES (?1, ?2, ?3, ?3, ?4)",
+            params![
+                timestamp,
+                RESOLUTION_RAW,
+                memory.used_bytes as i64,
+                memory.total_bytes as i64
+            ],
+        )?;
+        for disk in storage {
+            tx.execute(
+                "INSERT INTO StorageHistory (timestamp, resolution, mount_point, used_avg, used_max, total)
+                 VALUES (?1, ?2, ?3, ?4, ?4, ?5)",
+                params![
+                    timestamp,
+                    RESOLUTION_RAW,
+                    disk.mount_point.clone().unwrap_or_default(),
+                    disk.used_bytes as i64,
+                    disk.total_bytes as i64
+                ],
+            )?;
+        }
+        for sensor in temperatures {
+            tx.execute(
+                "INSERT INTO SensorHistory (timestamp, resolution, sensor, chip, temperature_avg, temperature_max)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+                params![
+                    timestamp,
+                    RESOLUTION_RAW,
+                    sensor.label,
+                    sensor.chip,
+                    sensor.current_celsius
+                ],
+            )?;
+        }
+        tx.commit()
+    }
+
+    // Folds raw samples into minute buckets and minute buckets into hour buckets
+    // once they fall out of their retention window, then drops expired hours.
+    pub fn compact(&self, conn: &Connection, now: i64) -> SqliteResult<()> {
+        let raw_cutoff = align(
+            now - i64::from(self.settings.raw_retention_secs),
+            RESOLUTION_MINUTE,
+        );
+        let minute_cutoff = align(
+            now - i64::from(self.settings.minute_retention_secs),
+            RESOLUTION_HOUR,
+        );
+        let hour_cutoff = now - i64::from(self.settings.hour_retention_secs);
+
+        let tx = conn.unchecked_transaction()?;
+        for (from, to, cutoff) in [
+            (RESOLUTION_RAW, RESOLUTION_MINUTE, raw_cutoff),
+            (RESOLUTION_MINUTE, RESOLUTION_HOUR, minute_cutoff),
+        ] {
+            tx.execute(
+                "INSERT INTO CpuHistory (timestamp, resolution, usage_avg, usage_max, frequency_mhz)
+                 SELECT (timestamp / ?2) * ?2, ?2, AVG(usage_avg), MAX(usage_max), CAST(AVG(frequency_mhz) AS INTEGER)
+                 FROM CpuHistory WHERE resolution = ?1 AND timestamp < ?3
+                 GROUP BY timestamp / ?2",
+                params![from, to, cutoff],
+            )?;
+            tx.execute(
+                "INSERT INTO MemoryHistory (timestamp, resolution, used_avg, used_max, total)
+                 SELECT (timestamp / ?2) * ?2, ?2, CAST(AVG(used_avg) AS INTEGER), MAX(used_max), MAX(total)
+                 FROM MemoryHistory WHERE resolution = ?1 AND timestamp < ?3
+                 GROUP BY timestamp / ?2",
+                params![from, to, cutoff],
+            )?;
+            tx.execute(
+                "INSERT INTO StorageHistory (timestamp, resolution, mount_point, used_avg, used_max, total)
+                 SELECT (timestamp / ?2) * ?2, ?2, mount_point, CAST(AVG(used_avg) AS INTEGER), MAX(used_max), MAX(total)
+                 FROM StorageHistory WHERE resolution = ?1 AND timestamp < ?3
+                 GROUP BY timestamp / ?2, mount_point",
+                params![from, to, cutoff],
+            )?;
+            tx.execute(
+                "INSERT INTO SensorHistory (timestamp, resolution, sensor, chip, temperature_avg, temperature_max)
+                 SELECT (timestamp / ?2) * ?2, ?2, sensor, chip, AVG(temperature_avg), MAX(temperature_max)
+                 FROM SensorHistory WHERE resolution = ?1 AND timestamp < ?3
+                 GROUP BY timestamp / ?2, sensor, chip",
+                params![from, to, cutoff],
+            )?;
+            for table in HISTORY_TABLES {
+                tx.execute(
+                    &format!("DELETE FROM {table} WHERE resolution = ?1 AND timestamp < ?2"),
+                    params![from, cutoff],
+                )?;
+            }
+        }
+        for table in HISTORY_TABLES {
+            tx.execute(
+                &format!("DELETE FROM {table} WHERE resolution = ?1 AND timestamp < ?2"),
+                params![RESOLUTION_HOUR, hour_cutoff],
+            )?;
+        }
+        tx.commit()
+    }
+
+    // Queries return every resolution in the range, oldest first
+    pub fn cpu_history(conn: &Connection, from: i64, to: i64) -> SqliteResult<Vec<CpuSample>> {
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, resolution, usage_avg, usage_max, frequency_mhz FROM CpuHistory
+             WHERE timestamp BETWEEN ?1 AND ?2 ORDER BY timestamp, resolution",
+        )?;
+        let rows = stmt.query_map(params![from, to], |row| {
+            Ok(CpuSample {
+                timestamp: row.get(0)?,
+                resolution: row.get(1)?,
+                usage_avg: row.get(2)?,
+                usage_max: row.get(3)?,
+                frequency_mhz: get_u64(row, 4)?,
+            })
+        })?;
+        rows.collect()
+    }
+    pub fn memory_history(
+        conn: &Connection,
+        from: i64,
+        to: i64,
+    ) -> SqliteResult<Vec<MemorySample>> {
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, resolution, used_avg, used_max, total FROM MemoryHistory
+             WHERE timestamp BETWEEN ?1 AND ?2 ORDER BY timestamp, resolution",
+        )?;
+        let rows = stmt.query_map(params![from, to], |row| {
+            Ok(MemorySample {
+                timestamp: row.get(0)?,
+                resolution: row.get(1)?,
+                used_avg_bytes: get_u64(row, 2)?,
+                used_max_bytes: get_u64(row, 3)?,
+                total_bytes: get_u64(row, 4)?,
+            })
+        })?;
+        rows.collect()
+    }
+    pub fn storage_history(
+        conn: &Connection,
+        from: i64,
+        to: i64,
+    ) -> SqliteResult<Vec<StorageSample>> {
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, resolution, mount_point, used_avg, used_max, total FROM StorageHistory
+             WHERE timestamp BETWEEN ?1 AND ?2 ORDER BY timestamp, resolution, mount_point",
+        )?;
+        let rows = stmt.query_map(params![from, to], |row| {
+            Ok(StorageSample {
+                timestamp: row.get(0)?,
+                resolution: row.get(1)?,
+                mount_point: row.get(2)?,
+                used_avg_bytes: get_u64(row, 3)?,
+                used_max_bytes: get_u64(row, 4)?,
+                total_bytes: get_u64(row, 5)?,
+            })
+        })?;
+        rows.collect()
+    }
+    pub fn sensor_history(
+        conn: &Connection,
+        from: i64,
+        to: i64,
+    ) -> SqliteResult<Vec<SensorSample>> {
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, resolution, sensor, chip, temperature_avg, temperature_max FROM SensorHistory
+             WHERE timestamp BETWEEN ?1 AND ?2 ORDER BY timestamp, resolution, sensor, chip",
+        )?;
+        let rows = stmt.query_map(params![from, to], |row| {
+            Ok(SensorSample {
+                timestamp: row.get(0)?,
+                resolution: row.get(1)?,
+                sensor: row.get(2)?,
+                chip: row.get(3)?,
+                temperature_avg: row.get(4)?,
+                temperature_max: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+// Rounds a timestamp down to the start of its bucket
+fn align(timestamp: i64, resolution: u32) -> i64 {
+    let resolution = i64::from(resolution);
+    timestamp.div_euclid(resolution) * resolution
+}
+
+// SQLite stores integers as i64; byte counts never get near the sign bit
+fn get_u64(row: &Row, index: usize) -> SqliteResult<u64> {
+    let value: i64 = row.get(index)?;
+    Ok(value.max(0) as u64)
+}
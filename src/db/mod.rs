@@ -1,3 +1,6 @@
+pub mod alerts;
+pub mod history;
 pub mod path;
+pub mod sampler;
 pub mod settings;
 pub mod window;
@@ -1,26 +1,24 @@
+use crate::error::{MachineInfoError, Result};
 use std::env;
-use std::error::Error;
 use std::fs;
 use std::path::PathBuf;
 use directories::ProjectDirs;
 
-pub fn set_db_path() -> Result<PathBuf, Box<dyn Error>> {
-    let mut path = PathBuf::new();
-
+pub fn set_db_path() -> Result<PathBuf> {
     let if_dev = get_if_dev();
-    if if_dev == Some(true) {
-        if let Ok(exe_path) = env::current_exe() {
-            fs::create_dir_all(exe_path.parent().unwrap().join("Resources")).expect("Unable to create directory -> Aborting!");
-            let dev_path = exe_path.parent().unwrap().join("Resources/app.db");
-            path = dev_path;
-        }
+    let path = if if_dev == Some(true) {
+        let exe_path = env::current_exe()?;
+        let exe_dir = exe_path
+            .parent()
+            .ok_or_else(|| MachineInfoError::NoData("executable directory".to_string()))?;
+        fs::create_dir_all(exe_dir.join("Resources"))?;
+        exe_dir.join("Resources/app.db")
     } else {
-        if let Some(project_dirs) = ProjectDirs::from("io", "github.northshorehero", "MachineInfo") {
-            fs::create_dir_all(project_dirs.config_dir()).expect("Unable to create directory -> Aborting!");
-            let prod_path = project_dirs.config_dir().join("app.db");
-            path = prod_path
-        }
-    }
+        let project_dirs = ProjectDirs::from("io", "github.northshorehero", "MachineInfo")
+            .ok_or_else(|| MachineInfoError::NoData("home directory".to_string()))?;
+        fs::create_dir_all(project_dirs.config_dir())?;
+        project_dirs.config_dir().join("app.db")
+    };
 
     // Return the pathway
     Ok(path)
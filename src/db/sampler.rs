@@ -0,0 +1,59 @@
+use crate::sampler::{DEFAULT_INTERVAL, clamp_interval};
+use crate::types::SamplerSettings;
+use rusqlite::{Connection, Result as SqliteResult, params};
+use std::time::Duration;
+
+impl Default for SamplerSettings {
+    fn default() -> Self {
+        SamplerSettings {
+            refresh_interval_ms: DEFAULT_INTERVAL.as_millis() as u32,
+        }
+    }
+}
+
+impl SamplerSettings {
+    pub fn create_table(conn: &Connection) -> SqliteResult<()> {
+        // Create the table only if it doesn't exist
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS SamplerSettings (
+            id INTEGER PRIMARY KEY,
+            refresh_interval INTEGER NOT NULL,
+            modified_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+            [],
+        )?;
+
+        // Insert default values ONLY if they don't exist (using INSERT OR IGNORE)
+        conn.execute(
+            "INSERT OR IGNORE INTO SamplerSettings (id, refresh_interval) VALUES (1, ?1)",
+            params![SamplerSettings::default().refresh_interval_ms],
+        )?;
+        Ok(())
+    }
+    pub fn save_to_db(&self, conn: &Connection) -> SqliteResult<()> {
+        conn.execute(
+            "INSERT INTO SamplerSettings (id, refresh_interval, modified_at)
+             VALUES (1, ?1, CURRENT_TIMESTAMP)
+             ON CONFLICT(id) DO UPDATE SET
+                 refresh_interval = excluded.refresh_interval,
+                 modified_at = CURRENT_TIMESTAMP",
+            params![self.refresh_interval_ms],
+        )?;
+        Ok(())
+    }
+    pub fn load_from_db(conn: &Connection) -> SqliteResult<SamplerSettings> {
+        conn.query_row(
+            "SELECT refresh_interval FROM SamplerSettings WHERE id = 1",
+            [],
+            |row| {
+                Ok(SamplerSettings {
+                    refresh_interval_ms: row.get(0)?,
+                })
+            },
+        )
+    }
+    // Stored value limited to what the sampler accepts
+    pub fn refresh_interval(&self) -> Duration {
+        clamp_interval(Duration::from_millis(u64::from(self.refresh_interval_ms)))
+    }
+}
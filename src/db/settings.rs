@@ -1,14 +1,13 @@
 use crate::db::path::set_db_path;
 use crate::types::Dimension;
-use rusqlite::{Connection, Result as SqliteResult};
+use crate::error::Result;
+use rusqlite::Connection;
 
-pub fn init_db() -> SqliteResult<Connection> {
+pub fn init_db() -> Result<Connection> {
     // Open the database
-    let db_path = set_db_path().map_err(|e| rusqlite::Error::InvalidParameterName(e.to_string()))?;
-    if let Some(is_dev) = crate::db::path::get_if_dev() {
-        if is_dev {
-            println!("Using development database at {}", db_path.display());
-        }
+    let db_path = set_db_path()?;
+    if let Some(true) = crate::db::path::get_if_dev() {
+        println!("Using development database at {}", db_path.display());
     }
     let conn = Connection::open(&db_path)?;
 
@@ -36,45 +35,45 @@ pub fn init_db() -> SqliteResult<Connection> {
     Ok(conn)
 }
 
-pub fn get_window_position(conn: &Connection) -> Dimension {
-    let mut my_dimension = Dimension::default();
-    my_dimension.x_position = conn
+pub fn get_window_position(conn: &Connection) -> Result<Dimension> {
+    let x_position = conn
         .query_row(
             "SELECT content FROM UserSettings WHERE item_name = 'WindowWidth'",
             [],
             |row| row.get(0),
-        )
-        .expect("Read Failure");
+        )?;
 
-    my_dimension.y_position = conn
+    let y_position = conn
         .query_row(
             "SELECT content FROM UserSettings WHERE item_name = 'WindowHeight'",
             [],
             |row| row.get(0),
-        )
-        .expect("Read Failure");
+        )?;
     // Return the dimensions
-    my_dimension
+    Ok(Dimension {
+        x_position,
+        y_position,
+    })
 }
 // Retrieves the currently stored entry from the database
 // Returns: Result containing the stored content string
-pub fn get_saved_entry(conn: &Connection) -> SqliteResult<String> {
-    conn.query_row("SELECT content FROM UserSettings WHERE id = 1", [], |row| {
+pub fn get_saved_entry(conn: &Connection) -> Result<String> {
+    Ok(conn.query_row("SELECT content FROM UserSettings WHERE id = 1", [], |row| {
         row.get(0)
-    })
+    })?)
 }
-pub fn set_saved_entry(conn: &Connection, entry: &str) {
-    conn.execute("UPDATE UserSettings SET content = ?1 WHERE id = 1", [entry])
-        .expect("Unable to Save Entry");
+pub fn set_saved_entry(conn: &Connection, entry: &str) -> Result<()> {
+    conn.execute("UPDATE UserSettings SET content = ?1 WHERE id = 1", [entry])?;
+    Ok(())
 }
 
-pub fn set_window_position(conn: &Connection, width: i32, height: i32) {
+pub fn set_window_position(conn: &Connection, width: i32, height: i32) -> Result<()> {
     conn.execute(
         "REPLACE INTO UserSettings (id, item_name, content)
          VALUES
             (2, 'WindowWidth', ?1),
             (3, 'WindowHeight', ?2)",
         [width, height],
-    )
-    .expect("Unable to Save Window Position");
+    )?;
+    Ok(())
 }
@@ -1,6 +1,7 @@
 use crate::WindowInformation;
 use crate::db::path::set_db_path;
-use rusqlite::{Connection, Result as SqliteResult};
+use crate::error::Result;
+use rusqlite::Connection;
 
 impl WindowInformation {
     // Write our getters and setters here
@@ -10,7 +11,7 @@ impl WindowInformation {
     pub fn get_height(&self) -> &u32 { &self.height }
     pub fn get_maximized(&self) -> &bool { &self.maximized }
     pub fn get_fullscreen(&self) -> &bool { &self.fullscreen }
-    pub fn get_all(&self) -> &WindowInformation { &self }
+    pub fn get_all(&self) -> &WindowInformation { self }
     pub fn set_x(&mut self, x: i32) { self.x = x }
     pub fn set_y(&mut self, y: i32) { self.y = y }
     pub fn set_width(&mut self, width: u32) { self.width = width }
@@ -26,13 +27,11 @@ impl WindowInformation {
         self.fullscreen = fullscreen;
     }
 
-    pub fn connect_to_db() -> SqliteResult<Connection> {
+    pub fn connect_to_db() -> Result<Connection> {
         // Open the database
-        let db_path = set_db_path().map_err(|e| rusqlite::Error::InvalidParameterName(e.to_string()))?;
-        if let Some(is_dev) = crate::db::path::get_if_dev() {
-            if is_dev {
-                println!("Using development database at {}", db_path.display());
-            }
+        let db_path = set_db_path()?;
+        if let Some(true) = crate::db::path::get_if_dev() {
+            println!("Using development database at {}", db_path.display());
         }
         let conn = Connection::open(&db_path)?;
 
@@ -62,7 +61,7 @@ impl WindowInformation {
         // Return the connection
         Ok(conn)
     }
-    pub fn save_to_db(&self, conn: &Connection) -> SqliteResult<()> {
+    pub fn save_to_db(&self, conn: &Connection) -> Result<()> {
         conn.execute(
             "INSERT INTO WindowSettings (id, x, y, width, height, maximized, fullscreen, modified_at)
              VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, CURRENT_TIMESTAMP)
@@ -78,7 +77,7 @@ impl WindowInformation {
         )?;
         Ok(())
     }
-    pub fn load_from_db(conn: &Connection) -> SqliteResult<WindowInformation> {
+    pub fn load_from_db(conn: &Connection) -> Result<WindowInformation> {
         let mut wi = WindowInformation::default();
         conn.query_row(
             "SELECT x, y, width, height, maximized, fullscreen FROM WindowSettings WHERE id = 1",
@@ -0,0 +1,249 @@
+use crate::display::{FeaturesDisplay, format_disk_size, format_memory_size};
+use crate::types::{CpuFeatures, Difference, MachineProfile, Storage};
+use std::collections::BTreeSet;
+
+// "Works on my machine": compares the parts of two profiles that describe the
+// machine itself. Live readings such as usage or free space change on every
+// refresh and are left out on purpose.
+
+pub fn diff_profiles(left: &MachineProfile, right: &MachineProfile) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    let mut compare =
+        |section: &str, item: &str, field: &str, l: Option<String>, r: Option<String>| {
+            if l != r {
+                differences.push(Difference {
+                    section: section.to_string(),
+                    item: item.to_string(),
+                    field: field.to_string(),
+                    left: l,
+                    right: r,
+                });
+            }
+        };
+
+    // Machine
+    let (l, r) = (&left.identity, &right.identity);
+    compare(
+        "machine",
+        "",
+        "Hostname",
+        l.hostname.clone(),
+        r.hostname.clone(),
+    );
+    compare(
+        "machine",
+        "",
+        "Operating System",
+        l.os_version.clone(),
+        r.os_version.clone(),
+    );
+    compare(
+        "machine",
+        "",
+        "Kernel",
+        l.kernel_version.clone(),
+        r.kernel_version.clone(),
+    );
+    compare(
+        "machine",
+        "",
+        "Architecture",
+        l.architecture.clone(),
+        r.architecture.clone(),
+    );
+
+    // CPU, a side without CPU readings has no values at all
+    let (l, r) = (left.snapshot.cpu.as_ref(), right.snapshot.cpu.as_ref());
+    compare(
+        "cpu",
+        "",
+        "CPU-ID",
+        l.and_then(|c| c.name.clone()),
+        r.and_then(|c| c.name.clone()),
+    );
+    compare(
+        "cpu",
+        "",
+        "Vendor",
+        l.and_then(|c| c.vendor.clone()),
+        r.and_then(|c| c.vendor.clone()),
+    );
+    compare(
+        "cpu",
+        "",
+        "Architecture",
+        l.and_then(|c| c.architecture.clone()),
+        r.and_then(|c| c.architecture.clone()),
+    );
+    compare(
+        "cpu",
+        "",
+        "Cores",
+        l.map(|c| c.cores.to_string()),
+        r.map(|c| c.cores.to_string()),
+    );
+    let (l, r) = (
+        l.and_then(|c| c.features.as_ref()),
+        r.and_then(|c| c.features.as_ref()),
+    );
+    let features = |features: Option<&CpuFeatures>| features.map(FeaturesDisplay::from);
+    let (l_display, r_display) = (features(l), features(r));
+    compare(
+        "cpu",
+        "",
+        "Model",
+        l_display.as_ref().map(|f| f.identification.clone()),
+        r_display.as_ref().map(|f| f.identification.clone()),
+    );
+    compare(
+        "cpu",
+        "",
+        "Microcode",
+        l.and_then(|f| f.microcode.clone()),
+        r.and_then(|f| f.microcode.clone()),
+    );
+    compare(
+        "cpu",
+        "",
+        "ISA Level",
+        l_display.map(|f| f.level).filter(|level| !level.is_empty()),
+        r_display.map(|f| f.level).filter(|level| !level.is_empty()),
+    );
+    // Flag by flag, but only when both profiles list flags at all
+    if let (Some(l), Some(r)) = (l, r) {
+        let flags: BTreeSet<&String> = l.flags.iter().chain(&r.flags).collect();
+        for flag in flags {
+            let present = |features: &CpuFeatures| {
+                features.flags.contains(flag).then(|| "present".to_string())
+            };
+            compare("cpu", flag, "Flag", present(l), present(r));
+        }
+    }
+
+    // Memory
+    let (l, r) = (
+        left.snapshot.memory.as_ref(),
+        right.snapshot.memory.as_ref(),
+    );
+    compare(
+        "memory",
+        "",
+        "Total Memory",
+        l.map(|m| format_memory_size(m.total_bytes)),
+        r.map(|m| format_memory_size(m.total_bytes)),
+    );
+    compare(
+        "memory",
+        "",
+        "Total Swap",
+        l.map(|m| format_memory_size(m.swap_total_bytes)),
+        r.map(|m| format_memory_size(m.swap_total_bytes)),
+    );
+
+    // Storage, disks are matched by mount point
+    let empty = Vec::new();
+    let l = left.snapshot.storage.as_ref().unwrap_or(&empty);
+    let r = right.snapshot.storage.as_ref().unwrap_or(&empty);
+    let mount_points: BTreeSet<&str> = l
+        .iter()
+        .chain(r.iter())
+        .filter_map(|disk| disk.mount_point.as_deref())
+        .collect();
+    for mount_point in mount_points {
+        let find = |disks: &[Storage]| {
+            disks
+                .iter()
+                .find(|disk| disk.mount_point.as_deref() == Some(mount_point))
+                .cloned()
+        };
+        let (l, r) = (find(l), find(r));
+        let present = |disk: &Option<Storage>| disk.as_ref().map(|_| "Mounted".to_string());
+        compare("storage", mount_point, "Mounted", present(&l), present(&r));
+        if l.is_none() || r.is_none() {
+            // Listing every property of a missing disk adds nothing
+            continue;
+        }
+        let (l, r) = (l.as_ref(), r.as_ref());
+        compare(
+            "storage",
+            mount_point,
+            "Drive Name",
+            l.and_then(|d| d.name.clone()),
+            r.and_then(|d| d.name.clone()),
+        );
+        compare(
+            "storage",
+            mount_point,
+            "File System",
+            l.and_then(|d| d.file_system.clone()),
+            r.and_then(|d| d.file_system.clone()),
+        );
+        compare(
+            "storage",
+            mount_point,
+            "Type",
+            l.and_then(|d| d.type_.clone()),
+            r.and_then(|d| d.type_.clone()),
+        );
+        compare(
+            "storage",
+            mount_point,
+            "Total Storage",
+            l.map(|d| format_disk_size(d.total_bytes)),
+            r.map(|d| format_disk_size(d.total_bytes)),
+        );
+    }
+
+    differences
+}
+
+pub fn render_text(left_name: &str, right_name: &str, differences: &[Difference]) -> String {
+    if differences.is_empty() {
+        return "No differences\n".to_string();
+    }
+    let label = |difference: &Difference| {
+        if difference.item.is_empty() {
+            format!("{} {}", difference.section, difference.field)
+        } else {
+            format!(
+                "{} {} {}",
+                difference.section, difference.item, difference.field
+            )
+        }
+    };
+    let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+
+    // Align the columns on the longest label and left value
+    let labels: Vec<String> = differences.iter().map(label).collect();
+    let label_width = labels
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max("Property".len());
+    let left_width = differences
+        .iter()
+        .map(|difference| value(&difference.left).len())
+        .max()
+        .unwrap_or(0)
+        .max(left_name.len());
+
+    let mut out = format!(
+        "{:<label_width$}  {:<left_width$}  {}\n",
+        "Property", left_name, right_name
+    );
+    for (label, difference) in labels.iter().zip(differences) {
+        out.push_str(&format!(
+            "{:<label_width$}  {:<left_width$}  {}\n",
+            label,
+            value(&difference.left),
+            value(&difference.right)
+        ));
+    }
+    out
+}
+
+#[cfg(feature = "serde")]
+pub fn render_json(differences: &[Difference]) -> String {
+    serde_json::to_string_pretty(differences).expect("differences always serialize")
+}
@@ -0,0 +1,565 @@
+use crate::types::{
+    CpuCache, CpuFeatures, CpuLoad, CpuTimes, CpuTopology, Fan, LoggedInUser, Memory, Network,
+    ProcessInfo, Processor, Storage, SystemInfo, Temperature,
+};
+
+// Presentation layer: turns the typed metrics into the strings shown to the user.
+// Nothing in `sys` should format values; keep all unit conversions here.
+
+// Declare Constants
+pub const BYTES_PER_GIB: f64 = 1_073_741_824.0; // 1024^3, used for memory
+pub const BYTES_PER_GB: f64 = 1_000_000_000.0; // 10^9, used for disks
+pub const MHZ_TO_GHZ: f64 = 1000.0;
+
+pub fn format_memory_size(bytes: u64) -> String {
+    format!("{:.2} GB", bytes as f64 / BYTES_PER_GIB)
+}
+
+pub fn format_disk_size(bytes: u64) -> String {
+    format!("{:.2} GB", bytes as f64 / BYTES_PER_GB)
+}
+
+// Scales a byte rate to the largest decimal unit below it
+pub fn format_rate(bytes_per_sec: f64) -> String {
+    const UNITS: [&str; 5] = ["B/s", "KB/s", "MB/s", "GB/s", "TB/s"];
+    let mut value = bytes_per_sec;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
+// Formats a Unix timestamp as "YYYY-MM-DD HH:MM:SS UTC"
+pub fn format_unix_time(secs: u64) -> String {
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let days = (secs / 86_400) as i64;
+    let seconds_of_day = secs % 86_400;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+// e.g. "3 days, 04:05:06", days are left out below one day
+pub fn format_duration(secs: u64) -> String {
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        secs % 86_400 / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    );
+    match secs / 86_400 {
+        0 => clock,
+        1 => format!("1 day, {}", clock),
+        days => format!("{} days, {}", days, clock),
+    }
+}
+
+// Cache sizes are powers of two, e.g. "48 KB", "1.25 MB", "32 MB"
+pub fn format_cache_size(bytes: u64) -> String {
+    const KIB: u64 = 1024;
+    const MIB: u64 = 1024 * 1024;
+    if bytes >= MIB && bytes.is_multiple_of(MIB) {
+        format!("{} MB", bytes / MIB)
+    } else if bytes >= MIB {
+        format!("{:.2} MB", bytes as f64 / MIB as f64)
+    } else {
+        format!("{} KB", bytes / KIB)
+    }
+}
+
+// Folds consecutive CPUs into ranges, the inverse of the sysfs cpu list format
+pub fn format_cpu_list(cpus: &[u32]) -> String {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for &cpu in cpus {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == cpu => *end = cpu,
+            _ => ranges.push((cpu, cpu)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn format_temperature(celsius: f32) -> String {
+    format!("{:.1} °C", celsius)
+}
+
+pub fn format_percent(percent: f32) -> String {
+    format!("{:.2} %", percent)
+}
+
+pub fn format_frequency(mhz: u64) -> String {
+    format!("{:.2} GHz", mhz as f64 / MHZ_TO_GHZ)
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ProcessorDisplay {
+    pub name: String,
+    pub vendor: String,
+    pub architecture: String,
+    pub speed: String,
+    pub cores: String,
+    pub usage: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MemoryDisplay {
+    pub total: String,
+    pub used: String,
+    pub free: String,
+    pub swap_total: String,
+    pub swap_used: String,
+    pub swap_free: String,
+    pub swap_in: String, // e.g. "12.0 pages/s"
+    pub swap_out: String,
+    pub buffers: String,
+    pub cached: String,
+    pub shared: String,
+    pub slab_reclaimable: String,
+    pub slab_unreclaimable: String,
+    pub dirty: String,
+    pub writeback: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct StorageDisplay {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub type_: String,
+    pub total_space: String,  // e.g. "500.00 GB"
+    pub free_space: String,   // e.g. "120.20 GB"
+    pub used_space: String,   // e.g. "379.80 GB"
+    pub percent_used: String, // e.g. "75.96 %"
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct NetworkDisplay {
+    pub name: String,
+    pub mac_address: String,
+    pub ipv4_addresses: String, // Comma separated
+    pub ipv6_addresses: String,
+    pub mtu: String,
+    pub link_state: String,
+    pub rx_rate: String, // e.g. "1.20 MB/s"
+    pub tx_rate: String,
+    pub packets: String, // e.g. "12 / 8", received / transmitted since last refresh
+    pub errors: String,
+    pub total_received: String,
+    pub total_transmitted: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TopologyDisplay {
+    pub sockets: String,
+    pub physical_cores: String,
+    pub logical_cpus: String,
+    pub threads_per_core: String,
+    pub caches: Vec<(String, String)>, // e.g. ("L1 Data", "48 KB × 8")
+    pub numa_nodes: Vec<(String, String)>, // e.g. ("Node 0", "0-7,16-23")
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct FeaturesDisplay {
+    pub identification: String, // e.g. "family 6, model 143, stepping 8"
+    pub microcode: String,
+    pub level: String, // e.g. "x86-64-v3", empty off x86-64
+    pub flags: String, // Space separated, like /proc/cpuinfo
+}
+
+// CPU states in top's order and abbreviations
+#[derive(Debug, Default, Clone)]
+pub struct TimesDisplay {
+    pub states: Vec<(String, String)>, // e.g. ("us", "12.0"), ("sy", "3.1"), ...
+    pub stalls: String,                // I/O wait and steal, e.g. "wa 4.0 st 0.0"
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct LoadDisplay {
+    pub averages: String,         // 1, 5 and 15 minutes, e.g. "1.20, 0.80, 0.50"
+    pub per_cpu: String,          // The same divided by the logical CPUs
+    pub run_queue: String,        // e.g. "3 running, 1 blocked"
+    pub context_switches: String, // e.g. "5120/s"
+    pub interrupts: String,
+    pub overloaded: bool, // More runnable tasks than CPUs over the last minute
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TemperatureDisplay {
+    pub label: String,
+    pub current: String,  // e.g. "54.0 °C"
+    pub max: String,      // Empty when the sensor has no throttle point
+    pub critical: String, // Empty when the sensor has no shutdown point
+    pub throttling: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct FanDisplay {
+    pub label: String,
+    pub speed: String, // e.g. "2400 RPM"
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SystemDisplay {
+    pub hostname: String,
+    pub os_name: String,
+    pub os_version: String,
+    pub kernel_version: String,
+    pub distribution_id: String,
+    pub architecture: String, // e.g. "arm64 (running as x86_64)"
+    pub uptime: String,       // e.g. "3 days, 04:05:06"
+    pub boot_time: String,    // e.g. "2025-01-02 03:04:05 UTC"
+    pub users: Vec<UserDisplay>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct UserDisplay {
+    pub name: String,
+    pub terminal: String,
+    pub host: String, // Empty for local sessions
+    pub login_time: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ProcessDisplay {
+    pub pid: String,
+    pub name: String,
+    pub user: String,
+    pub cpu_usage: String,
+    pub rss: String,
+    pub virtual_memory: String,
+    pub start_time: String,
+    pub command_line: String,
+}
+
+impl From<&Processor> for ProcessorDisplay {
+    fn from(cpu: &Processor) -> Self {
+        ProcessorDisplay {
+            name: cpu.name.clone().unwrap_or_default(),
+            vendor: cpu.vendor.clone().unwrap_or_default(),
+            architecture: cpu_architecture(cpu),
+            speed: format_frequency(cpu.frequency_mhz),
+            cores: format!("{}", cpu.cores),
+            usage: format_percent(cpu.usage_percent),
+        }
+    }
+}
+
+impl From<&Memory> for MemoryDisplay {
+    fn from(memory: &Memory) -> Self {
+        MemoryDisplay {
+            total: format_memory_size(memory.total_bytes),
+            used: format_memory_size(memory.used_bytes),
+            free: format_memory_size(memory.free_bytes),
+            swap_total: format_memory_size(memory.swap_total_bytes),
+            swap_used: format_memory_size(memory.swap_used_bytes),
+            swap_free: format_memory_size(memory.swap_free_bytes),
+            swap_in: format!("{:.1} pages/s", memory.swap_in_pages_per_sec),
+            swap_out: format!("{:.1} pages/s", memory.swap_out_pages_per_sec),
+            buffers: format_memory_size(memory.buffers_bytes),
+            cached: format_memory_size(memory.cached_bytes),
+            shared: format_memory_size(memory.shared_bytes),
+            slab_reclaimable: format_memory_size(memory.slab_reclaimable_bytes),
+            slab_unreclaimable: format_memory_size(memory.slab_unreclaimable_bytes),
+            dirty: format_memory_size(memory.dirty_bytes),
+            writeback: format_memory_size(memory.writeback_bytes),
+        }
+    }
+}
+
+impl From<&Storage> for StorageDisplay {
+    fn from(storage: &Storage) -> Self {
+        StorageDisplay {
+            name: storage.name.clone().unwrap_or_default(),
+            mount_point: storage.mount_point.clone().unwrap_or_default(),
+            file_system: storage.file_system.clone().unwrap_or_default(),
+            type_: storage.type_.clone().unwrap_or_default(),
+            total_space: format_disk_size(storage.total_bytes),
+            free_space: format_disk_size(storage.free_bytes),
+            used_space: format_disk_size(storage.used_bytes),
+            percent_used: format_percent(storage.percent_used),
+        }
+    }
+}
+
+impl From<&Network> for NetworkDisplay {
+    fn from(network: &Network) -> Self {
+        NetworkDisplay {
+            name: network.name.clone().unwrap_or_default(),
+            mac_address: network.mac_address.clone().unwrap_or_default(),
+            ipv4_addresses: network.ipv4_addresses.join(", "),
+            ipv6_addresses: network.ipv6_addresses.join(", "),
+            mtu: format!("{}", network.mtu),
+            link_state: network
+                .link_state
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+            rx_rate: format_rate(network.rx_bytes_per_sec),
+            tx_rate: format_rate(network.tx_bytes_per_sec),
+            packets: format!("{} / {}", network.rx_packets, network.tx_packets),
+            errors: format!("{} / {}", network.rx_errors, network.tx_errors),
+            total_received: format_disk_size(network.total_rx_bytes),
+            total_transmitted: format_disk_size(network.total_tx_bytes),
+        }
+    }
+}
+
+impl From<&ProcessInfo> for ProcessDisplay {
+    fn from(process: &ProcessInfo) -> Self {
+        ProcessDisplay {
+            pid: format!("{}", process.pid),
+            name: process.name.clone().unwrap_or_default(),
+            user: process.user.clone().unwrap_or_default(),
+            cpu_usage: format_percent(process.cpu_usage_percent),
+            rss: format_memory_size(process.rss_bytes),
+            virtual_memory: format_memory_size(process.virtual_bytes),
+            start_time: format_unix_time(process.start_time),
+            command_line: process.command_line.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&CpuTopology> for TopologyDisplay {
+    fn from(topology: &CpuTopology) -> Self {
+        TopologyDisplay {
+            sockets: format!("{}", topology.sockets),
+            physical_cores: format!("{}", topology.physical_cores),
+            logical_cpus: format!("{}", topology.logical_cpus),
+            threads_per_core: format!("{}", topology.threads_per_core),
+            caches: topology.caches.iter().map(cache_entry).collect(),
+            numa_nodes: topology
+                .numa_nodes
+                .iter()
+                .map(|node| (format!("Node {}", node.id), format_cpu_list(&node.cpus)))
+                .collect(),
+        }
+    }
+}
+
+// "x86_64 (family 6, model 143, stepping 8)", just the architecture when the
+// identification couldn't be read
+fn cpu_architecture(cpu: &Processor) -> String {
+    let architecture = cpu.architecture.clone().unwrap_or_default();
+    let identification = cpu
+        .features
+        .as_ref()
+        .map(|features| FeaturesDisplay::from(features).identification)
+        .unwrap_or_default();
+    match (architecture.is_empty(), identification.is_empty()) {
+        (_, true) => architecture,
+        (true, false) => identification,
+        (false, false) => format!("{} ({})", architecture, identification),
+    }
+}
+
+impl From<&CpuFeatures> for FeaturesDisplay {
+    fn from(features: &CpuFeatures) -> Self {
+        let identification: Vec<String> = [
+            ("family", features.family),
+            ("model", features.model),
+            ("stepping", features.stepping),
+        ]
+        .iter()
+        .filter_map(|(name, value)| Some(format!("{} {}", name, (*value)?)))
+        .collect();
+        FeaturesDisplay {
+            identification: identification.join(", "),
+            microcode: features.microcode.clone().unwrap_or_default(),
+            level: features
+                .x86_64_level()
+                .map(|level| format!("x86-64-v{}", level))
+                .unwrap_or_default(),
+            flags: features.flags.join(" "),
+        }
+    }
+}
+
+impl From<&CpuTimes> for TimesDisplay {
+    fn from(times: &CpuTimes) -> Self {
+        let states = [
+            ("us", times.user_percent),
+            ("sy", times.system_percent),
+            ("ni", times.nice_percent),
+            ("id", times.idle_percent),
+            ("wa", times.iowait_percent),
+            ("hi", times.irq_percent),
+            ("si", times.softirq_percent),
+            ("st", times.steal_percent),
+        ];
+        TimesDisplay {
+            states: states
+                .iter()
+                .map(|(name, percent)| (name.to_string(), format!("{:.1}", percent)))
+                .collect(),
+            stalls: format!(
+                "wa {:.1} st {:.1}",
+                times.iowait_percent, times.steal_percent
+            ),
+        }
+    }
+}
+
+impl TimesDisplay {
+    // "12.0 us, 3.1 sy, 0.5 ni, 80.2 id, 4.0 wa, 0.0 hi, 0.2 si, 0.0 st" like top
+    pub fn summary(&self) -> String {
+        self.states
+            .iter()
+            .map(|(name, percent)| format!("{} {}", percent, name))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+impl LoadDisplay {
+    pub fn new(load: &CpuLoad, logical_cpus: usize) -> Self {
+        let join = |values: [f64; 3]| {
+            values
+                .iter()
+                .map(|value| format!("{:.2}", value))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        let per_cpu = load.per_cpu(logical_cpus);
+        LoadDisplay {
+            averages: join([
+                load.load_average_1m,
+                load.load_average_5m,
+                load.load_average_15m,
+            ]),
+            per_cpu: join(per_cpu),
+            run_queue: format!(
+                "{} running, {} blocked",
+                load.running_processes, load.blocked_processes
+            ),
+            context_switches: format!("{:.0}/s", load.context_switches_per_sec),
+            interrupts: format!("{:.0}/s", load.interrupts_per_sec),
+            overloaded: per_cpu[0] > 1.0,
+        }
+    }
+}
+
+// "L1 Data" / "48 KB × 8", unified caches are just "L2", "L3"
+fn cache_entry(cache: &CpuCache) -> (String, String) {
+    let label = match cache.kind.as_str() {
+        "Unified" => format!("L{}", cache.level),
+        kind => format!("L{} {}", cache.level, kind),
+    };
+    let mut size = format_cache_size(cache.size_bytes);
+    if cache.instances > 1 {
+        size.push_str(&format!(" × {}", cache.instances));
+    }
+    (label, size)
+}
+
+impl TemperatureDisplay {
+    // e.g. "54.0 °C (max 80.0 °C, critical 100.0 °C)"
+    pub fn summary(&self) -> String {
+        let limits: Vec<String> = [("max", &self.max), ("critical", &self.critical)]
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(name, value)| format!("{} {}", name, value))
+            .collect();
+        let mut summary = self.current.clone();
+        if !limits.is_empty() {
+            summary.push_str(&format!(" ({})", limits.join(", ")));
+        }
+        if self.throttling {
+            summary.push_str(" throttling");
+        }
+        summary
+    }
+}
+
+impl From<&Temperature> for TemperatureDisplay {
+    fn from(temperature: &Temperature) -> Self {
+        TemperatureDisplay {
+            label: temperature.label.clone(),
+            current: format_temperature(temperature.current_celsius),
+            max: temperature
+                .max_celsius
+                .map(format_temperature)
+                .unwrap_or_default(),
+            critical: temperature
+                .critical_celsius
+                .map(format_temperature)
+                .unwrap_or_default(),
+            throttling: temperature.is_throttling(),
+        }
+    }
+}
+
+impl From<&Fan> for FanDisplay {
+    fn from(fan: &Fan) -> Self {
+        FanDisplay {
+            label: fan.label.clone(),
+            speed: format!("{} RPM", fan.rpm),
+        }
+    }
+}
+
+impl From<&SystemInfo> for SystemDisplay {
+    fn from(system: &SystemInfo) -> Self {
+        SystemDisplay {
+            hostname: system.hostname.clone().unwrap_or_default(),
+            os_name: system.os_name.clone().unwrap_or_default(),
+            os_version: system.os_version.clone().unwrap_or_default(),
+            kernel_version: system.kernel_version.clone().unwrap_or_default(),
+            distribution_id: system.distribution_id.clone().unwrap_or_default(),
+            architecture: system_architecture(system),
+            uptime: format_duration(system.uptime_secs),
+            boot_time: format_unix_time(system.boot_time),
+            users: system.users.iter().map(UserDisplay::from).collect(),
+        }
+    }
+}
+
+// The host, plus what this process runs as when Rosetta or Windows translates it
+fn system_architecture(system: &SystemInfo) -> String {
+    let host = system.architecture.clone().unwrap_or_default();
+    match &system.emulated_architecture {
+        Some(emulated) => format!("{} (running as {})", host, emulated),
+        None => host,
+    }
+}
+
+impl From<&LoggedInUser> for UserDisplay {
+    fn from(user: &LoggedInUser) -> Self {
+        UserDisplay {
+            name: user.name.clone(),
+            terminal: user.terminal.clone(),
+            host: user.host.clone().unwrap_or_default(),
+            login_time: format_unix_time(user.login_time),
+        }
+    }
+}
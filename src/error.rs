@@ -0,0 +1,64 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+// Crate-wide error so callers can tell "this platform can't do that" apart from
+// "you are not allowed to" and "there was nothing to read".
+#[derive(Debug)]
+pub enum MachineInfoError {
+    // The reading or action isn't available on this operating system
+    Unsupported(String),
+    // The operating system refused access
+    PermissionDenied(String),
+    // The source exists but returned nothing usable
+    NoData(String),
+    // A file or input could not be understood
+    Parse(String),
+    Io(io::Error),
+    Database(rusqlite::Error),
+}
+
+pub type Result<T> = std::result::Result<T, MachineInfoError>;
+
+impl fmt::Display for MachineInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MachineInfoError::Unsupported(what) => {
+                write!(f, "{} is not supported on this platform", what)
+            }
+            MachineInfoError::PermissionDenied(what) => write!(f, "Permission denied: {}", what),
+            MachineInfoError::NoData(what) => write!(f, "No data: {}", what),
+            MachineInfoError::Parse(what) => write!(f, "Unable to parse {}", what),
+            MachineInfoError::Io(e) => write!(f, "I/O error: {}", e),
+            MachineInfoError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl Error for MachineInfoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MachineInfoError::Io(e) => Some(e),
+            MachineInfoError::Database(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MachineInfoError {
+    fn from(e: io::Error) -> Self {
+        // Keep the kinds callers are expected to branch on
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => MachineInfoError::PermissionDenied(e.to_string()),
+            io::ErrorKind::Unsupported => MachineInfoError::Unsupported(e.to_string()),
+            io::ErrorKind::NotFound => MachineInfoError::NoData(e.to_string()),
+            _ => MachineInfoError::Io(e),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for MachineInfoError {
+    fn from(e: rusqlite::Error) -> Self {
+        MachineInfoError::Database(e)
+    }
+}
@@ -0,0 +1,482 @@
+use crate::display::{
+    FeaturesDisplay, LoadDisplay, MemoryDisplay, ProcessorDisplay, StorageDisplay, TimesDisplay,
+    TopologyDisplay, format_frequency, format_percent, format_unix_time,
+};
+use crate::error::{MachineInfoError, Result};
+#[cfg(feature = "serde")]
+use crate::types::MachineProfile;
+use crate::types::{CpuTimes, MachineIdentity, Snapshot};
+use directories::UserDirs;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Machine profiles for bug reports: the identity of the machine plus the CPU,
+// memory and storage readings of one snapshot, as a file that can be attached.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    // Matches the order of the File → Export Snapshot menu
+    pub fn from_index(index: i32) -> Self {
+        match index {
+            1 => ExportFormat::Csv,
+            2 => ExportFormat::Markdown,
+            _ => ExportFormat::Json,
+        }
+    }
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+        }
+    }
+    pub fn render(&self, identity: &MachineIdentity, snapshot: &Snapshot) -> Result<String> {
+        match self {
+            #[cfg(feature = "serde")]
+            ExportFormat::Json => Ok(render_json_export(identity, snapshot)),
+            #[cfg(not(feature = "serde"))]
+            ExportFormat::Json => Err(MachineInfoError::Unsupported(
+                "JSON export without the serde feature".to_string(),
+            )),
+            ExportFormat::Csv => Ok(render_csv(identity, snapshot)),
+            ExportFormat::Markdown => Ok(render_markdown(identity, snapshot)),
+        }
+    }
+}
+
+// Documents, then Downloads, then the home directory
+pub fn default_export_dir() -> Result<PathBuf> {
+    let dirs =
+        UserDirs::new().ok_or_else(|| MachineInfoError::NoData("home directory".to_string()))?;
+    let dir = dirs
+        .document_dir()
+        .or(dirs.download_dir())
+        .unwrap_or(dirs.home_dir());
+    Ok(dir.to_path_buf())
+}
+
+// e.g. "MachineInfo-myhost-20250102-030405.md"
+pub fn file_name(identity: &MachineIdentity, timestamp: i64, format: ExportFormat) -> String {
+    let host: String = identity
+        .hostname
+        .as_deref()
+        .unwrap_or("unknown")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    // "YYYY-MM-DD HH:MM:SS UTC" without separators
+    let time: String = format_unix_time(timestamp.max(0) as u64)
+        .chars()
+        .take(19)
+        .filter(char::is_ascii_digit)
+        .collect();
+    format!(
+        "MachineInfo-{}-{}-{}.{}",
+        host,
+        &time[..8],
+        &time[8..],
+        format.extension()
+    )
+}
+
+// Writes the export into `dir` and returns the path of the new file
+pub fn write_export(
+    dir: &Path,
+    format: ExportFormat,
+    identity: &MachineIdentity,
+    snapshot: &Snapshot,
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let contents = format.render(identity, snapshot)?;
+    let path = dir.join(file_name(identity, snapshot.timestamp, format));
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+// Only the sections a machine profile needs
+#[cfg(feature = "serde")]
+fn profile(snapshot: &Snapshot) -> Snapshot {
+    Snapshot {
+        timestamp: snapshot.timestamp,
+        cpu: snapshot.cpu.clone(),
+        memory: snapshot.memory.clone(),
+        storage: snapshot.storage.clone(),
+        ..Snapshot::default()
+    }
+}
+
+#[cfg(feature = "serde")]
+pub fn render_json_export(identity: &MachineIdentity, snapshot: &Snapshot) -> String {
+    let profile = MachineProfile {
+        identity: identity.clone(),
+        snapshot: profile(snapshot),
+    };
+    serde_json::to_string_pretty(&profile).expect("profiles always serialize")
+}
+
+// Reads back what render_json_export wrote, e.g. to compare two machines
+#[cfg(feature = "serde")]
+pub fn parse_profile(text: &str) -> Result<MachineProfile> {
+    serde_json::from_str(text).map_err(|e| MachineInfoError::Parse(format!("snapshot: {}", e)))
+}
+
+#[cfg(feature = "serde")]
+pub fn read_profile(path: &Path) -> Result<MachineProfile> {
+    parse_profile(&fs::read_to_string(path)?)
+}
+
+// One reading per row with raw units: section,item,field,value
+pub fn render_csv(identity: &MachineIdentity, snapshot: &Snapshot) -> String {
+    let mut rows: Vec<[String; 4]> = Vec::new();
+    let mut row = |section: &str, item: &str, field: &str, value: String| {
+        rows.push([
+            section.to_string(),
+            item.to_string(),
+            field.to_string(),
+            value,
+        ]);
+    };
+    let text = |value: &Option<String>| value.clone().unwrap_or_default();
+
+    row("machine", "", "timestamp", snapshot.timestamp.to_string());
+    row("machine", "", "hostname", text(&identity.hostname));
+    row("machine", "", "os_name", text(&identity.os_name));
+    row("machine", "", "os_version", text(&identity.os_version));
+    row(
+        "machine",
+        "",
+        "kernel_version",
+        text(&identity.kernel_version),
+    );
+    row("machine", "", "architecture", text(&identity.architecture));
+
+    if let Some(cpu) = &snapshot.cpu {
+        row("cpu", "", "name", text(&cpu.name));
+        row("cpu", "", "vendor", text(&cpu.vendor));
+        row("cpu", "", "architecture", text(&cpu.architecture));
+        row("cpu", "", "cores", cpu.cores.to_string());
+        if let Some(topology) = &cpu.topology {
+            row("cpu", "", "sockets", topology.sockets.to_string());
+            row(
+                "cpu",
+                "",
+                "physical_cores",
+                topology.physical_cores.to_string(),
+            );
+            row(
+                "cpu",
+                "",
+                "threads_per_core",
+                topology.threads_per_core.to_string(),
+            );
+        }
+        if let Some(features) = &cpu.features {
+            let number = |value: Option<u32>| value.map(|n| n.to_string()).unwrap_or_default();
+            row("cpu", "", "cpu_family", number(features.family));
+            row("cpu", "", "model", number(features.model));
+            row("cpu", "", "stepping", number(features.stepping));
+            row("cpu", "", "microcode", text(&features.microcode));
+            row("cpu", "", "flags", features.flags.join(" "));
+        }
+        row("cpu", "", "frequency_mhz", cpu.frequency_mhz.to_string());
+        row("cpu", "", "usage_percent", cpu.usage_percent.to_string());
+        if let Some(load) = &cpu.load {
+            row(
+                "cpu",
+                "",
+                "load_average_1m",
+                load.load_average_1m.to_string(),
+            );
+            row(
+                "cpu",
+                "",
+                "load_average_5m",
+                load.load_average_5m.to_string(),
+            );
+            row(
+                "cpu",
+                "",
+                "load_average_15m",
+                load.load_average_15m.to_string(),
+            );
+            row(
+                "cpu",
+                "",
+                "running_processes",
+                load.running_processes.to_string(),
+            );
+            row(
+                "cpu",
+                "",
+                "blocked_processes",
+                load.blocked_processes.to_string(),
+            );
+            row(
+                "cpu",
+                "",
+                "context_switches_per_sec",
+                load.context_switches_per_sec.to_string(),
+            );
+            row(
+                "cpu",
+                "",
+                "interrupts_per_sec",
+                load.interrupts_per_sec.to_string(),
+            );
+        }
+        // time_user_percent, time_nice_percent, ... for all CPUs and for each core
+        let states = |times: &CpuTimes| {
+            [
+                ("time_user_percent", times.user_percent),
+                ("time_nice_percent", times.nice_percent),
+                ("time_system_percent", times.system_percent),
+                ("time_idle_percent", times.idle_percent),
+                ("time_iowait_percent", times.iowait_percent),
+                ("time_irq_percent", times.irq_percent),
+                ("time_softirq_percent", times.softirq_percent),
+                ("time_steal_percent", times.steal_percent),
+            ]
+        };
+        if let Some(times) = &cpu.times {
+            for (field, value) in states(times) {
+                row("cpu", "", field, value.to_string());
+            }
+        }
+        for core in &cpu.per_core {
+            let item = format!("core{}", core.index);
+            row(
+                "cpu",
+                &item,
+                "usage_percent",
+                core.usage_percent.to_string(),
+            );
+            if let Some(times) = &core.times {
+                for (field, value) in states(times) {
+                    row("cpu", &item, field, value.to_string());
+                }
+            }
+            row(
+                "cpu",
+                &item,
+                "frequency_mhz",
+                core.frequency_mhz.to_string(),
+            );
+        }
+    }
+    if let Some(memory) = &snapshot.memory {
+        let fields = [
+            ("total_bytes", memory.total_bytes),
+            ("used_bytes", memory.used_bytes),
+            ("free_bytes", memory.free_bytes),
+            ("unused_bytes", memory.unused_bytes),
+            ("buffers_bytes", memory.buffers_bytes),
+            ("cached_bytes", memory.cached_bytes),
+            ("shared_bytes", memory.shared_bytes),
+            ("slab_reclaimable_bytes", memory.slab_reclaimable_bytes),
+            ("slab_unreclaimable_bytes", memory.slab_unreclaimable_bytes),
+            ("dirty_bytes", memory.dirty_bytes),
+            ("writeback_bytes", memory.writeback_bytes),
+            ("swap_total_bytes", memory.swap_total_bytes),
+            ("swap_used_bytes", memory.swap_used_bytes),
+            ("swap_free_bytes", memory.swap_free_bytes),
+        ];
+        for (field, value) in fields {
+            row("memory", "", field, value.to_string());
+        }
+    }
+    if let Some(storage) = &snapshot.storage {
+        for disk in storage {
+            let item = text(&disk.mount_point);
+            row("storage", &item, "name", text(&disk.name));
+            row("storage", &item, "file_system", text(&disk.file_system));
+            row("storage", &item, "type", text(&disk.type_));
+            row(
+                "storage",
+                &item,
+                "is_removable",
+                disk.is_removable.to_string(),
+            );
+            row(
+                "storage",
+                &item,
+                "total_bytes",
+                disk.total_bytes.to_string(),
+            );
+            row("storage", &item, "used_bytes", disk.used_bytes.to_string());
+            row("storage", &item, "free_bytes", disk.free_bytes.to_string());
+            row(
+                "storage",
+                &item,
+                "percent_used",
+                disk.percent_used.to_string(),
+            );
+        }
+    }
+
+    let mut out = String::from("section,item,field,value\n");
+    for fields in rows {
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+// Quotes a field when it contains a separator, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Human readable tables, meant to be pasted into an issue
+pub fn render_markdown(identity: &MachineIdentity, snapshot: &Snapshot) -> String {
+    let text = |value: &Option<String>| value.as_deref().unwrap_or("Unknown").to_string();
+    let mut out = String::from("# Machine Profile\n\n");
+    out.push_str(&format!(
+        "Taken {}\n\n",
+        format_unix_time(snapshot.timestamp.max(0) as u64)
+    ));
+
+    out.push_str("## Machine\n\n| Property | Value |\n| --- | --- |\n");
+    out.push_str(&table_row(&["Hostname", &text(&identity.hostname)]));
+    out.push_str(&table_row(&[
+        "Operating System",
+        &text(&identity.os_version),
+    ]));
+    out.push_str(&table_row(&["Kernel", &text(&identity.kernel_version)]));
+    out.push_str(&table_row(&["Architecture", &text(&identity.architecture)]));
+    out.push('\n');
+
+    if let Some(cpu) = &snapshot.cpu {
+        let display = ProcessorDisplay::from(cpu);
+        out.push_str("## CPU\n\n| Property | Value |\n| --- | --- |\n");
+        out.push_str(&table_row(&["CPU-ID", &display.name]));
+        out.push_str(&table_row(&["Vendor", &display.vendor]));
+        out.push_str(&table_row(&["Architecture", &display.architecture]));
+        if let Some(features) = &cpu.features {
+            let features = FeaturesDisplay::from(features);
+            if !features.microcode.is_empty() {
+                out.push_str(&table_row(&["Microcode", &features.microcode]));
+            }
+            if !features.level.is_empty() {
+                out.push_str(&table_row(&["ISA Level", &features.level]));
+            }
+            out.push_str(&table_row(&["Flags", &features.flags]));
+        }
+        out.push_str(&table_row(&["Logical CPUs", &display.cores]));
+        if let Some(topology) = &cpu.topology {
+            let topology = TopologyDisplay::from(topology);
+            out.push_str(&table_row(&["Sockets", &topology.sockets]));
+            out.push_str(&table_row(&["Physical Cores", &topology.physical_cores]));
+            out.push_str(&table_row(&[
+                "Threads per Core",
+                &topology.threads_per_core,
+            ]));
+            for (label, size) in &topology.caches {
+                out.push_str(&table_row(&[label, size]));
+            }
+        }
+        out.push_str(&table_row(&["Speed", &display.speed]));
+        out.push_str(&table_row(&["Usage", &display.usage]));
+        if let Some(load) = &cpu.load {
+            let load = LoadDisplay::new(load, cpu.cores);
+            out.push_str(&table_row(&["Load Average", &load.averages]));
+            out.push_str(&table_row(&["Load per CPU", &load.per_cpu]));
+            out.push_str(&table_row(&["Run Queue", &load.run_queue]));
+            out.push_str(&table_row(&["Context Switches", &load.context_switches]));
+            out.push_str(&table_row(&["Interrupts", &load.interrupts]));
+        }
+        if let Some(times) = &cpu.times {
+            out.push_str(&table_row(&[
+                "CPU Time",
+                &TimesDisplay::from(times).summary(),
+            ]));
+        }
+        // The time breakdown column only where /proc/stat was read
+        if cpu.per_core.iter().any(|core| core.times.is_some()) {
+            out.push_str("\n| Core | Usage | Speed | Time |\n| --- | --- | --- | --- |\n");
+        } else {
+            out.push_str("\n| Core | Usage | Speed |\n| --- | --- | --- |\n");
+        }
+        for core in &cpu.per_core {
+            let mut cells = vec![
+                core.index.to_string(),
+                format_percent(core.usage_percent),
+                format_frequency(core.frequency_mhz),
+            ];
+            if let Some(times) = &core.times {
+                cells.push(TimesDisplay::from(times).summary());
+            }
+            let cells: Vec<&str> = cells.iter().map(String::as_str).collect();
+            out.push_str(&table_row(&cells));
+        }
+        out.push('\n');
+    }
+    if let Some(memory) = &snapshot.memory {
+        let display = MemoryDisplay::from(memory);
+        out.push_str("## Memory\n\n| Property | Value |\n| --- | --- |\n");
+        for (label, value) in [
+            ("Total Memory", &display.total),
+            ("Used Memory", &display.used),
+            ("Free Memory", &display.free),
+            ("Buffers", &display.buffers),
+            ("Cached", &display.cached),
+            ("Shared", &display.shared),
+            ("Slab (reclaimable)", &display.slab_reclaimable),
+            ("Slab (unreclaimable)", &display.slab_unreclaimable),
+            ("Dirty", &display.dirty),
+            ("Writeback", &display.writeback),
+            ("Total Swap", &display.swap_total),
+            ("Used Swap", &display.swap_used),
+            ("Free Swap", &display.swap_free),
+        ] {
+            out.push_str(&table_row(&[label, value]));
+        }
+        out.push('\n');
+    }
+    if let Some(storage) = &snapshot.storage {
+        out.push_str("## Storage\n\n");
+        out.push_str(
+            "| Mount Point | Drive | File System | Type | Total | Used | Free | Used % |\n",
+        );
+        out.push_str("| --- | --- | --- | --- | --- | --- | --- | --- |\n");
+        for disk in storage {
+            let display = StorageDisplay::from(disk);
+            out.push_str(&table_row(&[
+                &display.mount_point,
+                &display.name,
+                &display.file_system,
+                &display.type_,
+                &display.total_space,
+                &display.used_space,
+                &display.free_space,
+                &display.percent_used,
+            ]));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn table_row(cells: &[&str]) -> String {
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|cell| cell.replace('|', "\\|").replace('\n', " "))
+        .collect();
+    format!("| {} |\n", cells.join(" | "))
+}
@@ -0,0 +1,615 @@
+use crate::display::FeaturesDisplay;
+use crate::error::Result;
+use crate::sys::collector::Collector;
+use crate::sys::provider::MetricsProvider;
+use crate::types::{CpuTimes, Snapshot, Subsystems};
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+// Prometheus / OpenMetrics exporter: serves the current readings at /metrics
+// so the machine can be scraped like a node exporter.
+
+// Declare Constants
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:9184";
+pub const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+const NAMESPACE: &str = "machineinfo";
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Writes metric families in the OpenMetrics text format
+#[derive(Debug, Default)]
+pub struct MetricsWriter {
+    out: String,
+}
+
+impl MetricsWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    // Starts a family, `unit` has to be the suffix of `name` when given
+    pub fn family(&mut self, name: &str, kind: &str, help: &str, unit: Option<&str>) -> &mut Self {
+        let _ = writeln!(self.out, "# TYPE {}_{} {}", NAMESPACE, name, kind);
+        if let Some(unit) = unit {
+            let _ = writeln!(self.out, "# UNIT {}_{} {}", NAMESPACE, name, unit);
+        }
+        let _ = writeln!(self.out, "# HELP {}_{} {}", NAMESPACE, name, help);
+        self
+    }
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) -> &mut Self {
+        let _ = write!(self.out, "{}_{}", NAMESPACE, name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+                .collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.out, " {}", format_value(value));
+        self
+    }
+    // Family with a single unlabelled sample
+    pub fn gauge(&mut self, name: &str, help: &str, unit: Option<&str>, value: f64) -> &mut Self {
+        self.family(name, "gauge", help, unit)
+            .sample(name, &[], value)
+    }
+    pub fn finish(mut self) -> String {
+        self.out.push_str("# EOF\n");
+        self.out
+    }
+}
+
+// Mode labels as node_exporter names them
+fn time_modes(times: &CpuTimes) -> [(&'static str, f32); 8] {
+    [
+        ("user", times.user_percent),
+        ("nice", times.nice_percent),
+        ("system", times.system_percent),
+        ("idle", times.idle_percent),
+        ("iowait", times.iowait_percent),
+        ("irq", times.irq_percent),
+        ("softirq", times.softirq_percent),
+        ("steal", times.steal_percent),
+    ]
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+// Renders one snapshot. `requested` tells which subsystems were asked for, so a
+// reader that failed shows up as machineinfo_collector_success 0.
+pub fn render_metrics(snapshot: &Snapshot, requested: Subsystems) -> String {
+    let mut writer = MetricsWriter::new();
+
+    if let Some(cpu) = &snapshot.cpu {
+        writer
+            .family("cpu", "info", "Processor identification", None)
+            .sample(
+                "cpu_info",
+                &[
+                    ("name", cpu.name.as_deref().unwrap_or_default()),
+                    ("vendor", cpu.vendor.as_deref().unwrap_or_default()),
+                    (
+                        "architecture",
+                        cpu.architecture.as_deref().unwrap_or_default(),
+                    ),
+                ],
+                1.0,
+            );
+        if let Some(features) = &cpu.features {
+            let number = |value: Option<u32>| value.map(|n| n.to_string()).unwrap_or_default();
+            let level = FeaturesDisplay::from(features).level;
+            writer
+                .family(
+                    "cpu_model",
+                    "info",
+                    "Processor family, model, stepping and microcode revision",
+                    None,
+                )
+                .sample(
+                    "cpu_model_info",
+                    &[
+                        ("cpu_family", &number(features.family)),
+                        ("model", &number(features.model)),
+                        ("stepping", &number(features.stepping)),
+                        (
+                            "microcode",
+                            features.microcode.as_deref().unwrap_or_default(),
+                        ),
+                        ("isa_level", &level),
+                    ],
+                    1.0,
+                );
+            writer.family(
+                "cpu_flag",
+                "info",
+                "Instruction-set flags the processor reports",
+                None,
+            );
+            for flag in &features.flags {
+                writer.sample("cpu_flag_info", &[("flag", flag)], 1.0);
+            }
+        }
+        writer.gauge(
+            "cpu_cores",
+            "Number of logical cores",
+            None,
+            cpu.cores as f64,
+        );
+        writer.gauge(
+            "cpu_usage_ratio",
+            "Average usage of all cores since the previous scrape",
+            Some("ratio"),
+            f64::from(cpu.usage_percent) / 100.0,
+        );
+        writer.gauge(
+            "cpu_frequency_hertz",
+            "Frequency of the first core",
+            Some("hertz"),
+            cpu.frequency_mhz as f64 * 1e6,
+        );
+        if let Some(load) = &cpu.load {
+            writer.family(
+                "cpu_load_average",
+                "gauge",
+                "Runnable and uninterruptible tasks averaged over 1, 5 and 15 minutes",
+                None,
+            );
+            for (window, value) in [
+                ("1m", load.load_average_1m),
+                ("5m", load.load_average_5m),
+                ("15m", load.load_average_15m),
+            ] {
+                writer.sample("cpu_load_average", &[("window", window)], value);
+            }
+            writer.gauge(
+                "processes_running",
+                "Tasks currently runnable",
+                None,
+                load.running_processes as f64,
+            );
+            writer.gauge(
+                "processes_blocked",
+                "Tasks waiting for I/O",
+                None,
+                load.blocked_processes as f64,
+            );
+            writer.gauge(
+                "cpu_context_switches_per_second",
+                "Context switches per second since the previous scrape",
+                None,
+                load.context_switches_per_sec,
+            );
+            writer.gauge(
+                "cpu_interrupts_per_second",
+                "Interrupts per second since the previous scrape",
+                None,
+                load.interrupts_per_sec,
+            );
+        }
+        if let Some(times) = &cpu.times {
+            writer.family(
+                "cpu_time_ratio",
+                "gauge",
+                "Share of time all CPUs spent in each state since the previous scrape",
+                Some("ratio"),
+            );
+            for (mode, percent) in time_modes(times) {
+                writer.sample(
+                    "cpu_time_ratio",
+                    &[("mode", mode)],
+                    f64::from(percent) / 100.0,
+                );
+            }
+        }
+        writer.family(
+            "cpu_core_usage_ratio",
+            "gauge",
+            "Usage of each core since the previous scrape",
+            Some("ratio"),
+        );
+        for core in &cpu.per_core {
+            let index = core.index.to_string();
+            writer.sample(
+                "cpu_core_usage_ratio",
+                &[("core", &index)],
+                f64::from(core.usage_percent) / 100.0,
+            );
+        }
+        if cpu.per_core.iter().any(|core| core.times.is_some()) {
+            writer.family(
+                "cpu_core_time_ratio",
+                "gauge",
+                "Share of time each core spent in each state since the previous scrape",
+                Some("ratio"),
+            );
+            for core in &cpu.per_core {
+                let index = core.index.to_string();
+                for (mode, percent) in core.times.iter().flat_map(time_modes) {
+                    writer.sample(
+                        "cpu_core_time_ratio",
+                        &[("core", &index), ("mode", mode)],
+                        f64::from(percent) / 100.0,
+                    );
+                }
+            }
+        }
+        writer.family(
+            "cpu_core_frequency_hertz",
+            "gauge",
+            "Frequency of each core",
+            Some("hertz"),
+        );
+        for core in &cpu.per_core {
+            let index = core.index.to_string();
+            writer.sample(
+                "cpu_core_frequency_hertz",
+                &[("core", &index)],
+                core.frequency_mhz as f64 * 1e6,
+            );
+        }
+    }
+
+    if let Some(memory) = &snapshot.memory {
+        let bytes = [
+            ("memory_total_bytes", "Installed memory", memory.total_bytes),
+            ("memory_used_bytes", "Memory in use", memory.used_bytes),
+            (
+                "memory_available_bytes",
+                "Memory available to new applications",
+                memory.free_bytes,
+            ),
+            (
+                "memory_unused_bytes",
+                "Memory not used for anything",
+                memory.unused_bytes,
+            ),
+            (
+                "memory_buffers_bytes",
+                "Block device buffers",
+                memory.buffers_bytes,
+            ),
+            ("memory_cached_bytes", "Page cache", memory.cached_bytes),
+            ("memory_shared_bytes", "Shared memory", memory.shared_bytes),
+            (
+                "memory_slab_reclaimable_bytes",
+                "Kernel slab that can be reclaimed",
+                memory.slab_reclaimable_bytes,
+            ),
+            (
+                "memory_slab_unreclaimable_bytes",
+                "Kernel slab that cannot be reclaimed",
+                memory.slab_unreclaimable_bytes,
+            ),
+            (
+                "memory_dirty_bytes",
+                "Memory waiting to be written to disk",
+                memory.dirty_bytes,
+            ),
+            (
+                "memory_writeback_bytes",
+                "Memory being written to disk",
+                memory.writeback_bytes,
+            ),
+            ("swap_total_bytes", "Swap space", memory.swap_total_bytes),
+            (
+                "swap_used_bytes",
+                "Swap space in use",
+                memory.swap_used_bytes,
+            ),
+            (
+                "swap_free_bytes",
+                "Swap space not in use",
+                memory.swap_free_bytes,
+            ),
+        ];
+        for (name, help, value) in bytes {
+            writer.gauge(name, help, Some("bytes"), value as f64);
+        }
+        writer.gauge(
+            "swap_in_pages_per_second",
+            "Pages swapped in per second",
+            None,
+            memory.swap_in_pages_per_sec,
+        );
+        writer.gauge(
+            "swap_out_pages_per_second",
+            "Pages swapped out per second",
+            None,
+            memory.swap_out_pages_per_sec,
+        );
+    }
+
+    if let Some(storage) = &snapshot.storage {
+        let families = [
+            ("storage_size_bytes", "Size of the file system"),
+            ("storage_used_bytes", "Space in use"),
+            ("storage_available_bytes", "Space available"),
+        ];
+        for (index, (name, help)) in families.iter().enumerate() {
+            writer.family(name, "gauge", help, Some("bytes"));
+            for disk in storage {
+                let value = match index {
+                    0 => disk.total_bytes,
+                    1 => disk.used_bytes,
+                    _ => disk.free_bytes,
+                };
+                writer.sample(name, &disk_labels(disk), value as f64);
+            }
+        }
+        writer.family("storage_removable", "gauge", "1 for removable drives", None);
+        for disk in storage {
+            writer.sample(
+                "storage_removable",
+                &disk_labels(disk),
+                f64::from(u8::from(disk.is_removable)),
+            );
+        }
+    }
+
+    if let Some(network) = &snapshot.network {
+        writer.family("network_up", "gauge", "1 when the link is up", None);
+        for interface in network {
+            let up = interface.link_state.as_deref() == Some("up");
+            writer.sample(
+                "network_up",
+                &[("interface", interface.name.as_deref().unwrap_or_default())],
+                f64::from(u8::from(up)),
+            );
+        }
+        writer.family(
+            "network_mtu_bytes",
+            "gauge",
+            "Maximum transmission unit",
+            Some("bytes"),
+        );
+        for interface in network {
+            writer.sample(
+                "network_mtu_bytes",
+                &[("interface", interface.name.as_deref().unwrap_or_default())],
+                interface.mtu as f64,
+            );
+        }
+        let counters = [
+            ("network_receive_bytes", "Bytes received", Some("bytes")),
+            ("network_transmit_bytes", "Bytes transmitted", Some("bytes")),
+            ("network_receive_packets", "Packets received", None),
+            ("network_transmit_packets", "Packets transmitted", None),
+            ("network_receive_errors", "Receive errors", None),
+            ("network_transmit_errors", "Transmit errors", None),
+        ];
+        for (index, (name, help, unit)) in counters.iter().enumerate() {
+            writer.family(name, "counter", help, *unit);
+            for interface in network {
+                let value = match index {
+                    0 => interface.total_rx_bytes,
+                    1 => interface.total_tx_bytes,
+                    2 => interface.total_rx_packets,
+                    3 => interface.total_tx_packets,
+                    4 => interface.total_rx_errors,
+                    _ => interface.total_tx_errors,
+                };
+                writer.sample(
+                    &format!("{}_total", name),
+                    &[("interface", interface.name.as_deref().unwrap_or_default())],
+                    value as f64,
+                );
+            }
+        }
+    }
+
+    if let Some(system) = &snapshot.system {
+        writer
+            .family("system", "info", "Operating system identification", None)
+            .sample(
+                "system_info",
+                &[
+                    ("hostname", system.hostname.as_deref().unwrap_or_default()),
+                    ("os", system.os_name.as_deref().unwrap_or_default()),
+                    (
+                        "os_version",
+                        system.os_version.as_deref().unwrap_or_default(),
+                    ),
+                    (
+                        "kernel",
+                        system.kernel_version.as_deref().unwrap_or_default(),
+                    ),
+                    ("arch", system.architecture.as_deref().unwrap_or_default()),
+                ],
+                1.0,
+            );
+        writer.gauge(
+            "system_boot_time_seconds",
+            "Unix time the system booted",
+            Some("seconds"),
+            system.boot_time as f64,
+        );
+        writer.gauge(
+            "system_uptime_seconds",
+            "Time since the system booted",
+            Some("seconds"),
+            system.uptime_secs as f64,
+        );
+        writer.gauge(
+            "system_users",
+            "Number of login sessions",
+            None,
+            system.users.len() as f64,
+        );
+    }
+
+    if let Some(sensors) = &snapshot.sensors {
+        if !sensors.temperatures.is_empty() {
+            writer.family(
+                "sensor_temperature_celsius",
+                "gauge",
+                "Current temperature of each sensor",
+                Some("celsius"),
+            );
+            for sensor in &sensors.temperatures {
+                writer.sample(
+                    "sensor_temperature_celsius",
+                    &[("sensor", &sensor.label), ("chip", &sensor.chip)],
+                    f64::from(sensor.current_celsius),
+                );
+            }
+        }
+        if !sensors.fans.is_empty() {
+            writer.family(
+                "fan_speed_rpm",
+                "gauge",
+                "Current speed of each fan",
+                Some("rpm"),
+            );
+            for fan in &sensors.fans {
+                writer.sample(
+                    "fan_speed_rpm",
+                    &[("fan", &fan.label), ("chip", &fan.chip)],
+                    fan.rpm as f64,
+                );
+            }
+        }
+    }
+
+    // One line per requested subsystem so a broken reader is visible in Prometheus
+    writer.family(
+        "collector_success",
+        "gauge",
+        "1 when the subsystem could be read during this scrape",
+        None,
+    );
+    let collectors = [
+        ("cpu", requested.cpu, snapshot.cpu.is_some()),
+        ("memory", requested.memory, snapshot.memory.is_some()),
+        ("storage", requested.storage, snapshot.storage.is_some()),
+        ("network", requested.network, snapshot.network.is_some()),
+        ("system", requested.system, snapshot.system.is_some()),
+        ("sensors", requested.sensors, snapshot.sensors.is_some()),
+    ];
+    for (subsystem, wanted, read) in collectors {
+        if wanted {
+            writer.sample(
+                "collector_success",
+                &[("subsystem", subsystem)],
+                f64::from(u8::from(read)),
+            );
+        }
+    }
+
+    writer.finish()
+}
+
+fn disk_labels(disk: &crate::types::Storage) -> [(&str, &str); 3] {
+    [
+        ("device", disk.name.as_deref().unwrap_or_default()),
+        (
+            "mountpoint",
+            disk.mount_point.as_deref().unwrap_or_default(),
+        ),
+        ("fstype", disk.file_system.as_deref().unwrap_or_default()),
+    ]
+}
+
+// Answers scrapes until the process is stopped. Every scrape takes a fresh
+// snapshot, so rates cover the time since the previous scrape.
+// Clients are served one at a time: one that never finishes its request holds
+// up the scrapes behind it for up to CLIENT_TIMEOUT (5 s), which is fine for a
+// handful of Prometheus servers but not for exposing the port to the internet.
+pub fn serve<P: MetricsProvider>(
+    listener: TcpListener,
+    collector: &mut Collector<P>,
+    subsystems: Subsystems,
+) -> Result<()> {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                // A client that misbehaves only loses its own scrape
+                if let Err(e) = handle_connection(stream, collector, subsystems) {
+                    eprintln!("Error: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+    Ok(())
+}
+
+fn handle_connection<P: MetricsProvider>(
+    mut stream: TcpStream,
+    collector: &mut Collector<P>,
+    subsystems: Subsystems,
+) -> Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+
+    // Only the request line matters, the headers are read and dropped
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header != "\r\n" && header != "\n" {
+        header.clear();
+    }
+
+    // Scrapers may add parameters, e.g. /metrics?name[]=up, none of them are used
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next();
+    let path = parts
+        .next()
+        .map(|target| target.split(['?', '#']).next().unwrap_or(target));
+    let (status, content_type, body) = match (method, path) {
+        (Some("GET"), Some("/metrics")) => {
+            let (snapshot, errors) = collector.refresh(subsystems);
+            for e in errors {
+                eprintln!("Error: {}", e);
+            }
+            (
+                "200 OK",
+                CONTENT_TYPE,
+                render_metrics(&snapshot, subsystems),
+            )
+        }
+        (Some("GET"), Some("/")) => (
+            "200 OK",
+            "text/html; charset=utf-8",
+            "<html><body><h1>MachineInfo Exporter</h1>\
+             <p><a href=\"/metrics\">Metrics</a></p></body></html>\n"
+                .to_string(),
+        ),
+        (Some("GET"), Some(_)) => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "Not Found\n".to_string(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "Method Not Allowed\n".to_string(),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
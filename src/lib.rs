@@ -1,13 +1,31 @@
+pub mod alerts;
+pub mod chart;
+pub mod cli;
 pub mod db;
+pub mod diff;
+pub mod display;
+pub mod error;
+pub mod export;
+pub mod exporter;
+pub mod sampler;
 pub mod sys;
 pub mod types;
 pub use crate::sys::*;
 pub use crate::db::settings::*;
+pub use crate::error::MachineInfoError;
 pub use crate::types::*;
 
 pub mod prelude {
     pub use crate::db::settings::*;
-    pub use crate::sys::{memory, processor, storage};
+    pub use crate::display::*;
+    pub use crate::error::MachineInfoError;
+    pub use crate::sys::collector::Collector;
+    pub use crate::sys::fake::FakeProvider;
+    pub use crate::sys::provider::{MetricsProvider, SysinfoProvider};
+    pub use crate::sys::{
+        features, load, memory, network, process, processor, sensors, storage, system, times,
+        topology,
+    };
     pub use crate::types::*;
 }
 
@@ -1,27 +1,63 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use machine_info::{Dimension, Memory, Processor, Storage, WindowInformation};
+use machine_info::alerts::{self, AlertEvaluator};
+use machine_info::chart::{ChartBuffer, ChartRange};
+use machine_info::cli::{self, CliOptions};
+use machine_info::display::{
+    FanDisplay, FeaturesDisplay, LoadDisplay, MemoryDisplay, NetworkDisplay, ProcessDisplay,
+    ProcessorDisplay, StorageDisplay, SystemDisplay, TemperatureDisplay, TimesDisplay,
+    TopologyDisplay, format_frequency, format_memory_size, format_percent,
+};
+use machine_info::export::{self, ExportFormat};
+use machine_info::sampler::{Sampler, SamplerEvent};
+use machine_info::sys::collector::Collector;
+use machine_info::{
+    AlertEvent, AlertRule, CpuFeatures, Dimension, HistoryRecorder, MachineIdentity, Memory,
+    Network, ProcessInfo, ProcessSignal, ProcessSortKey, Processor, SamplerSettings, Sensors,
+    Snapshot, Storage, Subsystems, SystemInfo, WindowInformation,
+};
+use slint::{ModelRc, StandardListViewItem, VecModel};
+use std::cell::RefCell;
 use std::env;
 use std::error::Error;
-use std::sync::Arc;
+use std::process::ExitCode;
+use std::rc::Rc;
+use std::time::Instant;
 slint::include_modules!();
 
-fn main() -> Result<(), Box<dyn Error>> {
-    // Get connection to database
-    let conn = Arc::new(WindowInformation::connect_to_db()?);
-    let wi = WindowInformation::load_from_db(&conn)?;
-
-    // Get connection to disks
-    let mut _storage_connection = Storage::get_storage_connection();
-    let _storage = Storage::get_storage_info(&mut _storage_connection);
+// Sidebar indices of the pages that are only refreshed while shown
+const CPU_PAGE: i32 = 0;
+const STORAGE_PAGE: i32 = 2;
+const NETWORK_PAGE: i32 = 3;
+const PROCESS_PAGE: i32 = 4;
+const SYSTEM_PAGE: i32 = 5;
 
-    // Get CPU information
-    let mut _cpu_connection = Processor::set_cpu_connection();
-    let _cpu = Processor::get_cpu_info(&mut _cpu_connection);
+fn main() -> Result<ExitCode, Box<dyn Error>> {
+    // Run headless when any command-line option is given
+    match CliOptions::parse(env::args().skip(1)) {
+        Ok(Some(options)) => {
+            cli::attach_parent_console();
+            return cli::run(&options);
+        }
+        Ok(None) => {}
+        Err(message) => {
+            cli::attach_parent_console();
+            eprintln!("{}", message);
+            return Ok(ExitCode::from(2));
+        }
+    }
 
-    // Get memory information
-    let mut _memory_connection = Memory::set_memory_connection();
-    let _memory = Memory::get_memory_info(&mut _memory_connection);
+    // Get connection to database
+    let conn = Rc::new(WindowInformation::connect_to_db()?);
+    let wi = WindowInformation::load_from_db(&conn)?;
+    SamplerSettings::create_table(&conn)?;
+    let sampler_settings = SamplerSettings::load_from_db(&conn)?;
+    AlertRule::create_table(&conn)?;
+    AlertEvent::create_table(&conn)?;
+    let alert_evaluator = Rc::new(RefCell::new(AlertEvaluator::new(AlertRule::load_all(&conn)?)));
+    // History is written from the sampling thread through its own connection
+    let history_conn = WindowInformation::connect_to_db()?;
+    let recorder = HistoryRecorder::from_db(&history_conn)?;
 
     // Initialize UI components
     let ui = AppWindow::new()?;
@@ -31,51 +67,173 @@ fn main() -> Result<(), Box<dyn Error>> {
     //    ui.set_input_text(saved_entry.into());
     //}
 
-    // Pass CPU to UI
-    ui.set_cpu_id(_cpu.name.unwrap_or_default().into());
-    ui.set_cpu_vendor(_cpu.vendor.unwrap_or_default().into());
-    ui.set_cpu_speed(_cpu.speed.unwrap_or_default().into());
-    ui.set_cpu_cores(_cpu.cores.unwrap_or_default().into());
-    ui.set_cpu_usage(_cpu.usage.unwrap_or_default().into());
-    ui.set_cpu_family(_cpu.family.unwrap_or_default().into());
-
-    // Pass Memory to UI
-    ui.set_memory_total(_memory.total.unwrap_or_default().into());
-    ui.set_memory_used(_memory.used.unwrap_or_default().into());
-    ui.set_memory_free(_memory.free.unwrap_or_default().into());
-
-    // Pass Storage to UI
-    ui.set_storage_name(_storage.name.unwrap_or_default().into());
-    ui.set_storage_total(_storage.total_space.unwrap_or_default().into());
-    ui.set_storage_used(_storage.used_space.unwrap_or_default().into());
-    ui.set_storage_free(_storage.free_space.unwrap_or_default().into());
-    ui.set_storage_percent_used(_storage.percent_used.unwrap_or_default().into());
-
-    // Refresh
+    // Sample on a background thread, the first snapshot fills every page once
+    // and the process table waits until it is opened
+    let sampler = Rc::new(
+        Sampler::builder(Collector::new())
+            .with_interval(sampler_settings.refresh_interval())
+            .with_subsystems(Subsystems {
+                processes: false,
+                ..Subsystems::all()
+            })
+            .with_history(recorder, history_conn)
+            .spawn({
+                let ui_handle = ui.as_weak();
+                move || {
+                    // Hop onto the UI thread, the snapshot itself waits in the sampler
+                    let ui_handle = ui_handle.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = ui_handle.upgrade() {
+                            ui.invoke_file_refresh();
+                        }
+                    });
+                }
+            }),
+    );
+    sampler.set_subsystems(subsystems_for_page(ui.get_current_page()));
+    let processes = Rc::new(RefCell::new(Vec::<ProcessInfo>::new()));
+    let charts = Rc::new(RefCell::new(UsageCharts::default()));
+    // Latest reading of every subsystem, for exports
+    let latest = Rc::new(RefCell::new(Snapshot::default()));
+
+    // Refresh, drains what the sampler has collected since the last call
     ui.on_file_refresh({
         let ui_handle = ui.as_weak();
+        let charts = Rc::clone(&charts);
+        let sampler = Rc::clone(&sampler);
+        let processes = Rc::clone(&processes);
+        let latest = Rc::clone(&latest);
+        let alert_evaluator = Rc::clone(&alert_evaluator);
+        let conn = Rc::clone(&conn);
         move || {
             let ui = ui_handle.unwrap();
-            // Get system information
-            let _cpu = Processor::get_cpu_info(&mut _cpu_connection);
-            let _memory = Memory::get_memory_info(&mut _memory_connection);
-            let _storage = Storage::get_storage_info(&mut _storage_connection);
-            // Pass CPU to UI
-            ui.set_cpu_id(_cpu.name.unwrap_or_default().into());
-            ui.set_cpu_vendor(_cpu.vendor.unwrap_or_default().into());
-            ui.set_cpu_speed(_cpu.speed.unwrap_or_default().into());
-            ui.set_cpu_cores(_cpu.cores.unwrap_or_default().into());
-            ui.set_cpu_usage(_cpu.usage.unwrap_or_default().into());
-            ui.set_cpu_family(_cpu.family.unwrap_or_default().into());
-            // Pass Memory to UI
-            ui.set_memory_total(_memory.total.unwrap_or_default().into());
-            ui.set_memory_used(_memory.used.unwrap_or_default().into());
-            ui.set_memory_free(_memory.free.unwrap_or_default().into());
-            // Pass Storage to the UI.
-            ui.set_storage_name(_storage.name.unwrap_or_default().into());
-            ui.set_storage_total(_storage.total_space.unwrap_or_default().into());
-            ui.set_storage_used(_storage.used_space.unwrap_or_default().into());
-            ui.set_storage_free(_storage.free_space.unwrap_or_default().into());
+            while let Some(event) = sampler.try_recv() {
+                match event {
+                    SamplerEvent::Snapshot(snapshot) => {
+                        // Pass system information to UI
+                        update_ui(&ui, &snapshot);
+                        remember_snapshot(&mut latest.borrow_mut(), &snapshot);
+                        // Check the alert rules against the new readings
+                        for event in alert_evaluator.borrow_mut().evaluate(&snapshot) {
+                            if let Err(e) = event.save_to_db(&conn) {
+                                eprintln!("Error: Unable to log alert: {}", e);
+                            }
+                            alerts::notify_desktop(&event);
+                            ui.set_alert_banner(event.message.into());
+                        }
+                        // Append to the history charts
+                        charts.borrow_mut().push(&snapshot);
+                        charts.borrow().render(&ui);
+                        if let Some(list) = snapshot.processes {
+                            *processes.borrow_mut() = list;
+                            update_process_ui(&ui, &processes.borrow());
+                        }
+                    }
+                    SamplerEvent::SignalSent {
+                        pid,
+                        signal,
+                        result,
+                    } => {
+                        let message = match result {
+                            Ok(()) => format!("Sent {:?} to process {}", signal, pid),
+                            Err(e) => format!("Error: {}", e),
+                        };
+                        ui.set_process_message(message.into());
+                    }
+                }
+            }
+        }
+    });
+
+    // Only read what the new page shows
+    ui.on_page_changed({
+        let ui_handle = ui.as_weak();
+        let sampler = Rc::clone(&sampler);
+        move || {
+            sampler.set_subsystems(subsystems_for_page(ui_handle.unwrap().get_current_page()));
+        }
+    });
+
+    // Change and remember how often the sampler runs
+    ui.on_refresh_interval_changed({
+        let sampler = Rc::clone(&sampler);
+        let conn = Rc::clone(&conn);
+        move |interval_ms| {
+            let settings = SamplerSettings {
+                refresh_interval_ms: interval_ms.max(0) as u32,
+            };
+            sampler.set_interval(settings.refresh_interval());
+            if let Err(e) = settings.save_to_db(&conn) {
+                eprintln!("Error: Unable to save refresh interval: {}", e);
+            }
+        }
+    });
+
+    // Write the last readings to a file that can be attached to a bug report
+    ui.on_export_snapshot({
+        let ui_handle = ui.as_weak();
+        let latest = Rc::clone(&latest);
+        move |format| {
+            let format = ExportFormat::from_index(format);
+            let identity = MachineIdentity::read();
+            let result = export::default_export_dir()
+                .and_then(|dir| export::write_export(&dir, format, &identity, &latest.borrow()));
+            let message = match result {
+                Ok(path) => format!("Exported snapshot to {}", path.display()),
+                Err(e) => format!("Error: Unable to export snapshot: {}", e),
+            };
+            ui_handle.unwrap().set_status_message(message.into());
+        }
+    });
+
+    // Redraw the charts from the buffers when the range changes
+    ui.on_chart_range_changed({
+        let ui_handle = ui.as_weak();
+        let charts = Rc::clone(&charts);
+        move || {
+            charts.borrow().render(&ui_handle.unwrap());
+        }
+    });
+
+    // Re-filter the flags of the last CPU reading
+    ui.on_cpu_flags_filter_changed({
+        let ui_handle = ui.as_weak();
+        let latest = Rc::clone(&latest);
+        move || {
+            let latest = latest.borrow();
+            if let Some(features) = latest.cpu.as_ref().and_then(|cpu| cpu.features.as_ref()) {
+                update_cpu_features_ui(&ui_handle.unwrap(), features);
+            }
+        }
+    });
+
+    // Re-filter and re-sort the last process list without collecting again
+    ui.on_process_view_changed({
+        let ui_handle = ui.as_weak();
+        let processes = Rc::clone(&processes);
+        move || {
+            update_process_ui(&ui_handle.unwrap(), &processes.borrow());
+        }
+    });
+
+    // Send SIGTERM / SIGKILL to the confirmed process, the result arrives with the next
+    // refresh. Name and start time are the ones the user confirmed, so the sampler
+    // refuses when the pid has been reused since.
+    ui.on_process_signal({
+        let sampler = Rc::clone(&sampler);
+        move |pid, name, start_time, kill| {
+            let signal = if kill {
+                ProcessSignal::Kill
+            } else {
+                ProcessSignal::Terminate
+            };
+            let target = ProcessInfo {
+                pid: pid as u32,
+                name: Some(name.to_string()),
+                start_time: start_time.parse().unwrap_or_default(),
+                ..Default::default()
+            };
+            sampler.send_signal(target, signal);
         }
     });
 
@@ -93,7 +251,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     // Close file handling
     ui.window().on_close_requested({
         let ui_handle = ui.as_weak();
-        let conn = Arc::clone(&conn);
+        let conn = Rc::clone(&conn);
         let mut wi = WindowInformation::default();
         move || {
             // get scale factor of display
@@ -125,9 +283,10 @@ fn main() -> Result<(), Box<dyn Error>> {
     // Configure launching of application
     let weak_app = ui.as_weak();
     //let dimensions = db_controls::get_window_position(&conn);
-    let mut app_dimensions = Dimension::default();
-    app_dimensions.x_position = Some(*wi.get_x());
-    app_dimensions.y_position = Some(*wi.get_y());
+    let app_dimensions = Dimension {
+        x_position: Some(*wi.get_x()),
+        y_position: Some(*wi.get_y()),
+    };
     let app_width = *wi.get_width() as f32;
     let app_height = *wi.get_height() as f32;
     let app_size = slint::LogicalSize::new(app_width, app_height);
@@ -154,5 +313,365 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     // Launch application event loop
     ui.run()?;
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+// The charts always need CPU and memory, everything else is only read while its page is shown
+fn subsystems_for_page(page: i32) -> Subsystems {
+    Subsystems {
+        cpu: true,
+        memory: true,
+        storage: page == STORAGE_PAGE,
+        network: page == NETWORK_PAGE,
+        processes: page == PROCESS_PAGE,
+        system: page == SYSTEM_PAGE,
+        sensors: page == CPU_PAGE,
+    }
+}
+
+// Keeps the newest reading of each subsystem, pages that are not shown are not refreshed
+fn remember_snapshot(latest: &mut Snapshot, snapshot: &Snapshot) {
+    latest.timestamp = snapshot.timestamp;
+    if let Some(cpu) = &snapshot.cpu {
+        latest.cpu = Some(cpu.clone());
+    }
+    if let Some(memory) = &snapshot.memory {
+        latest.memory = Some(memory.clone());
+    }
+    if let Some(storage) = &snapshot.storage {
+        latest.storage = Some(storage.clone());
+    }
+    if let Some(network) = &snapshot.network {
+        latest.network = Some(network.clone());
+    }
+    if let Some(system) = &snapshot.system {
+        latest.system = Some(system.clone());
+    }
+    if let Some(sensors) = &snapshot.sensors {
+        latest.sensors = Some(sensors.clone());
+    }
+}
+
+// Formats the collected metrics and pushes them into the Slint properties.
+// Pages that were not refreshed keep their last values.
+fn update_ui(ui: &AppWindow, snapshot: &Snapshot) {
+    if let Some(cpu) = &snapshot.cpu {
+        update_cpu_ui(ui, cpu);
+    }
+    if let Some(memory) = &snapshot.memory {
+        update_memory_ui(ui, memory);
+    }
+    if let Some(storage) = &snapshot.storage {
+        update_storage_ui(ui, storage);
+    }
+    if let Some(network) = &snapshot.network {
+        update_network_ui(ui, network);
+    }
+    if let Some(system) = &snapshot.system {
+        update_system_ui(ui, system);
+    }
+    if let Some(sensors) = &snapshot.sensors {
+        update_sensors_ui(ui, sensors);
+    }
+}
+
+// Pass CPU to UI
+fn update_cpu_ui(ui: &AppWindow, cpu: &Processor) {
+    let cores: Vec<CoreEntry> = cpu
+        .per_core
+        .iter()
+        .map(|core| CoreEntry {
+            index: core.index as i32,
+            usage: core.usage_percent / 100.0,
+            usage_text: format_percent(core.usage_percent).into(),
+            speed: format_frequency(core.frequency_mhz).into(),
+            stalls: core
+                .times
+                .as_ref()
+                .map(|times| TimesDisplay::from(times).stalls)
+                .unwrap_or_default()
+                .into(),
+        })
+        .collect();
+    ui.set_cpu_per_core(ModelRc::new(VecModel::from(cores)));
+    // Platforms without topology keep the "Unknown" defaults
+    if let Some(topology) = &cpu.topology {
+        let topology = TopologyDisplay::from(topology);
+        let entries: Vec<TopologyEntry> = topology
+            .caches
+            .into_iter()
+            .chain(
+                topology
+                    .numa_nodes
+                    .into_iter()
+                    .map(|(label, cpus)| (label, format!("CPUs {}", cpus))),
+            )
+            .map(|(label, value)| TopologyEntry {
+                label: label.into(),
+                value: value.into(),
+            })
+            .collect();
+        ui.set_cpu_topology(ModelRc::new(VecModel::from(entries)));
+        ui.set_cpu_sockets(topology.sockets.into());
+        ui.set_cpu_physical_cores(topology.physical_cores.into());
+        ui.set_cpu_threads_per_core(topology.threads_per_core.into());
+    }
+    if let Some(features) = &cpu.features {
+        update_cpu_features_ui(ui, features);
+    }
+    if let Some(times) = &cpu.times {
+        ui.set_cpu_times(TimesDisplay::from(times).summary().into());
+    }
+    if let Some(load) = &cpu.load {
+        let load = LoadDisplay::new(load, cpu.cores);
+        ui.set_cpu_load_average(load.averages.into());
+        ui.set_cpu_load_per_cpu(load.per_cpu.into());
+        ui.set_cpu_overloaded(load.overloaded);
+        ui.set_cpu_run_queue(load.run_queue.into());
+        ui.set_cpu_context_switches(load.context_switches.into());
+        ui.set_cpu_interrupts(load.interrupts.into());
+    }
+    let cpu = ProcessorDisplay::from(cpu);
+    ui.set_cpu_id(cpu.name.into());
+    ui.set_cpu_vendor(cpu.vendor.into());
+    ui.set_cpu_speed(cpu.speed.into());
+    ui.set_cpu_cores(cpu.cores.into());
+    ui.set_cpu_usage(cpu.usage.into());
+    ui.set_cpu_architecture(cpu.architecture.into());
+}
+
+// Microcode, ISA level and the flags matching the search box
+fn update_cpu_features_ui(ui: &AppWindow, features: &CpuFeatures) {
+    let display = FeaturesDisplay::from(features);
+    if !display.microcode.is_empty() {
+        ui.set_cpu_microcode(display.microcode.into());
+    }
+    if !display.level.is_empty() {
+        ui.set_cpu_isa_level(display.level.into());
+    }
+    let flags = features.search_flags(&ui.get_cpu_flags_filter());
+    ui.set_cpu_flags_status(format!("{} of {} flags", flags.len(), features.flags.len()).into());
+    ui.set_cpu_flags(flags.join(" ").into());
+}
+
+// Pass Sensors to UI, temperatures first
+fn update_sensors_ui(ui: &AppWindow, sensors: &Sensors) {
+    let temperatures = sensors.temperatures.iter().map(|sensor| {
+        let display = TemperatureDisplay::from(sensor);
+        SensorEntry {
+            value: display.summary().into(),
+            label: display.label.into(),
+            throttling: display.throttling,
+        }
+    });
+    let fans = sensors.fans.iter().map(|fan| {
+        let display = FanDisplay::from(fan);
+        SensorEntry {
+            label: display.label.into(),
+            value: display.speed.into(),
+            throttling: false,
+        }
+    });
+    let entries: Vec<SensorEntry> = temperatures.chain(fans).collect();
+    ui.set_cpu_sensors(ModelRc::new(VecModel::from(entries)));
+}
+
+// Pass Memory to UI
+fn update_memory_ui(ui: &AppWindow, memory: &Memory) {
+    let mut start = 0.0;
+    let segments: Vec<MemorySegment> = memory
+        .get_segments()
+        .iter()
+        .map(|segment| {
+            let fraction = if memory.total_bytes > 0 {
+                (segment.bytes as f64 / memory.total_bytes as f64) as f32
+            } else {
+                0.0
+            };
+            let entry = MemorySegment {
+                label: segment.label.clone().into(),
+                size: format_memory_size(segment.bytes).into(),
+                start,
+                fraction,
+            };
+            start += fraction;
+            entry
+        })
+        .collect();
+    ui.set_memory_segments(ModelRc::new(VecModel::from(segments)));
+    let memory = MemoryDisplay::from(memory);
+    ui.set_memory_total(memory.total.into());
+    ui.set_memory_used(memory.used.into());
+    ui.set_memory_free(memory.free.into());
+    ui.set_swap_total(memory.swap_total.into());
+    ui.set_swap_used(memory.swap_used.into());
+    ui.set_swap_free(memory.swap_free.into());
+    ui.set_swap_in(memory.swap_in.into());
+    ui.set_swap_out(memory.swap_out.into());
+    ui.set_memory_buffers(memory.buffers.into());
+    ui.set_memory_cached(memory.cached.into());
+    ui.set_memory_shared(memory.shared.into());
+    ui.set_memory_slab_reclaimable(memory.slab_reclaimable.into());
+    ui.set_memory_slab_unreclaimable(memory.slab_unreclaimable.into());
+    ui.set_memory_dirty(memory.dirty.into());
+    ui.set_memory_writeback(memory.writeback.into());
+}
+
+// Pass Storage to UI
+fn update_storage_ui(ui: &AppWindow, storage: &[Storage]) {
+    let disks: Vec<StorageEntry> = storage
+        .iter()
+        .map(|disk| {
+            let display = StorageDisplay::from(disk);
+            StorageEntry {
+                name: display.name.into(),
+                mount_point: display.mount_point.into(),
+                file_system: display.file_system.into(),
+                kind: display.type_.into(),
+                removable: disk.is_removable,
+                total: display.total_space.into(),
+                used: display.used_space.into(),
+                free: display.free_space.into(),
+                percent_used: display.percent_used.into(),
+                usage: disk.percent_used / 100.0,
+            }
+        })
+        .collect();
+    ui.set_storage_disks(ModelRc::new(VecModel::from(disks)));
+}
+
+// Pass Network to UI
+fn update_network_ui(ui: &AppWindow, network: &[Network]) {
+    let interfaces: Vec<NetworkEntry> = network
+        .iter()
+        .map(|interface| {
+            let display = NetworkDisplay::from(interface);
+            NetworkEntry {
+                name: display.name.into(),
+                link_state: display.link_state.into(),
+                mac_address: display.mac_address.into(),
+                ipv4_addresses: display.ipv4_addresses.into(),
+                ipv6_addresses: display.ipv6_addresses.into(),
+                mtu: display.mtu.into(),
+                rx_rate: display.rx_rate.into(),
+                tx_rate: display.tx_rate.into(),
+                packets: display.packets.into(),
+                errors: display.errors.into(),
+                total_received: display.total_received.into(),
+                total_transmitted: display.total_transmitted.into(),
+            }
+        })
+        .collect();
+    ui.set_network_interfaces(ModelRc::new(VecModel::from(interfaces)));
+}
+
+// Pass System to UI
+fn update_system_ui(ui: &AppWindow, system: &SystemInfo) {
+    let system = SystemDisplay::from(system);
+    let users: Vec<UserEntry> = system
+        .users
+        .into_iter()
+        .map(|user| UserEntry {
+            name: user.name.into(),
+            terminal: user.terminal.into(),
+            host: user.host.into(),
+            login_time: user.login_time.into(),
+        })
+        .collect();
+    ui.set_system_users(ModelRc::new(VecModel::from(users)));
+    ui.set_system_hostname(system.hostname.into());
+    ui.set_system_os_name(system.os_name.into());
+    ui.set_system_os_version(system.os_version.into());
+    ui.set_system_kernel(system.kernel_version.into());
+    ui.set_system_distribution(system.distribution_id.into());
+    ui.set_system_architecture(system.architecture.into());
+    ui.set_system_uptime(system.uptime.into());
+    ui.set_system_boot_time(system.boot_time.into());
+}
+
+fn update_process_ui(ui: &AppWindow, processes: &[ProcessInfo]) {
+    let mut visible = ProcessInfo::filter(processes, &ui.get_process_filter());
+    ProcessInfo::sort(
+        &mut visible,
+        ProcessSortKey::from_column(ui.get_process_sort_column()),
+        ui.get_process_sort_ascending(),
+    );
+
+    let pids: Vec<i32> = visible.iter().map(|process| process.pid as i32).collect();
+    let start_times: Vec<slint::SharedString> = visible
+        .iter()
+        .map(|process| process.start_time.to_string().into())
+        .collect();
+    let rows: Vec<ModelRc<StandardListViewItem>> = visible
+        .iter()
+        .map(|process| {
+            let display = ProcessDisplay::from(process);
+            let cells: Vec<StandardListViewItem> = [
+                display.pid,
+                display.name,
+                display.user,
+                display.cpu_usage,
+                display.rss,
+                display.virtual_memory,
+                display.start_time,
+                display.command_line,
+            ]
+            .into_iter()
+            .map(|cell| StandardListViewItem::from(slint::SharedString::from(cell)))
+            .collect();
+            ModelRc::new(VecModel::from(cells))
+        })
+        .collect();
+
+    // Keep the selection on the same process even when rows move
+    let selected_pid = ui.get_process_selected_pid();
+    let current_row = pids
+        .iter()
+        .position(|pid| *pid == selected_pid)
+        .map_or(-1, |row| row as i32);
+    if current_row < 0 {
+        ui.set_process_selected_pid(-1);
+    }
+    ui.set_process_rows(ModelRc::new(VecModel::from(rows)));
+    ui.set_process_pids(ModelRc::new(VecModel::from(pids)));
+    ui.set_process_start_times(ModelRc::new(VecModel::from(start_times)));
+    ui.set_process_current_row(current_row);
+    ui.set_process_status(format!("{} of {} processes", visible.len(), processes.len()).into());
+}
+
+// Rolling usage history behind the CPU and Memory charts
+struct UsageCharts {
+    cpu: ChartBuffer,
+    memory: ChartBuffer,
+}
+
+impl Default for UsageCharts {
+    fn default() -> Self {
+        UsageCharts {
+            cpu: ChartBuffer::for_longest_range(),
+            memory: ChartBuffer::for_longest_range(),
+        }
+    }
+}
+
+impl UsageCharts {
+    fn push(&mut self, snapshot: &Snapshot) {
+        if let Some(cpu) = &snapshot.cpu {
+            self.cpu.push(cpu.usage_percent);
+        }
+        if let Some(memory) = &snapshot.memory {
+            let memory_percent = if memory.total_bytes > 0 {
+                (memory.used_bytes as f64 / memory.total_bytes as f64 * 100.0) as f32
+            } else {
+                0.0
+            };
+            self.memory.push(memory_percent);
+        }
+    }
+    fn render(&self, ui: &AppWindow) {
+        let range = ChartRange::from_index(ui.get_chart_range());
+        let now = Instant::now();
+        ui.set_cpu_history(self.cpu.to_path_commands(range, now).into());
+        ui.set_memory_history(self.memory.to_path_commands(range, now).into());
+    }
 }
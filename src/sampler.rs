@@ -0,0 +1,225 @@
+use crate::db::history::unix_now;
+use crate::error::Result;
+use crate::sys::collector::Collector;
+use crate::sys::provider::{MetricsProvider, SysinfoProvider};
+use crate::types::{
+    HistoryRecorder, ProcessInfo, ProcessSignal, Snapshot, Subsystems, Temperature,
+};
+use rusqlite::Connection;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Background sampling: a worker thread owns the collector and reads the machine
+// on its own schedule, so a slow reader never blocks whoever shows the results.
+
+// Declare Constants
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+pub const MIN_INTERVAL: Duration = Duration::from_millis(250);
+pub const MAX_INTERVAL: Duration = Duration::from_secs(60);
+
+// What the worker hands back
+#[derive(Debug)]
+pub enum SamplerEvent {
+    Snapshot(Box<Snapshot>),
+    SignalSent {
+        pid: u32,
+        signal: ProcessSignal,
+        result: Result<()>,
+    },
+}
+
+enum Command {
+    Subsystems(Subsystems),
+    Interval(Duration),
+    Signal(ProcessInfo, ProcessSignal),
+    RefreshNow,
+    Stop,
+}
+
+pub struct SamplerBuilder<P: MetricsProvider = SysinfoProvider> {
+    collector: Collector<P>,
+    interval: Duration,
+    subsystems: Subsystems,
+    history: Option<(HistoryRecorder, Connection)>,
+}
+
+pub struct Sampler {
+    commands: Sender<Command>,
+    events: Receiver<SamplerEvent>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<P: MetricsProvider + Send + 'static> SamplerBuilder<P> {
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = clamp_interval(interval);
+        self
+    }
+    pub fn with_subsystems(mut self, subsystems: Subsystems) -> Self {
+        self.subsystems = subsystems;
+        self
+    }
+    // Records every snapshot into the history tables through the given connection
+    pub fn with_history(mut self, recorder: HistoryRecorder, conn: Connection) -> Self {
+        self.history = Some((recorder, conn));
+        self
+    }
+    // Starts the worker. `notify` runs on the worker thread after every event is
+    // queued, use it to wake the receiving side (e.g. slint::invoke_from_event_loop).
+    pub fn spawn<F>(self, notify: F) -> Sampler
+    where
+        F: Fn() + Send + 'static,
+    {
+        let (command_tx, command_rx) = mpsc::channel();
+        let (event_tx, event_rx) = mpsc::channel();
+        let worker = thread::Builder::new()
+            .name("sampler".to_string())
+            .spawn(move || run(self, command_rx, event_tx, notify))
+            .expect("Unable to start the sampling thread");
+        Sampler {
+            commands: command_tx,
+            events: event_rx,
+            worker: Some(worker),
+        }
+    }
+}
+
+impl Sampler {
+    pub fn builder<P: MetricsProvider + Send + 'static>(
+        collector: Collector<P>,
+    ) -> SamplerBuilder<P> {
+        SamplerBuilder {
+            collector,
+            interval: DEFAULT_INTERVAL,
+            subsystems: Subsystems::all(),
+            history: None,
+        }
+    }
+    // Changes what gets read and takes a snapshot right away
+    pub fn set_subsystems(&self, subsystems: Subsystems) {
+        let _ = self.commands.send(Command::Subsystems(subsystems));
+    }
+    pub fn set_interval(&self, interval: Duration) {
+        let _ = self
+            .commands
+            .send(Command::Interval(clamp_interval(interval)));
+    }
+    // The result comes back as a SamplerEvent::SignalSent
+    pub fn send_signal(&self, target: ProcessInfo, signal: ProcessSignal) {
+        let _ = self.commands.send(Command::Signal(target, signal));
+    }
+    pub fn refresh_now(&self) {
+        let _ = self.commands.send(Command::RefreshNow);
+    }
+    // Next queued event, if any
+    pub fn try_recv(&self) -> Option<SamplerEvent> {
+        self.events.try_recv().ok()
+    }
+    // Waits for the next event, None once the worker has stopped
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<SamplerEvent> {
+        self.events.recv_timeout(timeout).ok()
+    }
+    pub fn stop(&mut self) {
+        let _ = self.commands.send(Command::Stop);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+pub fn clamp_interval(interval: Duration) -> Duration {
+    interval.clamp(MIN_INTERVAL, MAX_INTERVAL)
+}
+
+fn run<P, F>(
+    builder: SamplerBuilder<P>,
+    commands: Receiver<Command>,
+    events: Sender<SamplerEvent>,
+    notify: F,
+) where
+    P: MetricsProvider,
+    F: Fn(),
+{
+    let SamplerBuilder {
+        mut collector,
+        mut interval,
+        mut subsystems,
+        mut history,
+    } = builder;
+    let send = |event: SamplerEvent| {
+        let sent = events.send(event).is_ok();
+        if sent {
+            notify();
+        }
+        sent
+    };
+
+    loop {
+        let started = Instant::now();
+
+        // History needs CPU, memory, storage and sensors together whenever a sample is due
+        let mut wanted = subsystems;
+        if let Some((recorder, _)) = &history
+            && recorder.is_due(unix_now())
+        {
+            wanted.cpu = true;
+            wanted.memory = true;
+            wanted.storage = true;
+            wanted.sensors = true;
+        }
+        let (snapshot, errors) = collector.refresh(wanted);
+        for e in errors {
+            eprintln!("Error: {}", e);
+        }
+        // A failed write should never stop sampling
+        if let Some((recorder, conn)) = history.as_mut()
+            && let (Some(cpu), Some(memory), Some(storage)) =
+                (&snapshot.cpu, &snapshot.memory, &snapshot.storage)
+            && let Err(e) = recorder.record(conn, cpu, memory, storage, temperatures(&snapshot))
+        {
+            eprintln!("Error: Unable to record history: {}", e);
+        }
+        if !send(SamplerEvent::Snapshot(Box::new(snapshot))) {
+            // Nobody is listening anymore
+            return;
+        }
+
+        // Sleep until the next tick, handling commands as they arrive
+        loop {
+            let timeout = (started + interval).saturating_duration_since(Instant::now());
+            match commands.recv_timeout(timeout) {
+                Ok(Command::Subsystems(next)) => {
+                    subsystems = next;
+                    break;
+                }
+                Ok(Command::Interval(next)) => interval = next,
+                Ok(Command::Signal(target, signal)) => {
+                    let result = collector.send_signal(&target, signal);
+                    send(SamplerEvent::SignalSent {
+                        pid: target.pid,
+                        signal,
+                        result,
+                    });
+                }
+                Ok(Command::RefreshNow) => break,
+                Err(RecvTimeoutError::Timeout) => break,
+                Ok(Command::Stop) | Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    }
+}
+
+// Machines without sensors still record the other subsystems
+fn temperatures(snapshot: &Snapshot) -> &[Temperature] {
+    snapshot
+        .sensors
+        .as_ref()
+        .map(|sensors| sensors.temperatures.as_slice())
+        .unwrap_or_default()
+}
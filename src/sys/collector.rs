@@ -0,0 +1,115 @@
+use crate::db::history::unix_now;
+use crate::error::{MachineInfoError, Result};
+use crate::sys::load::LoadCounters;
+use crate::sys::memory::SwapCounters;
+use crate::sys::provider::{MetricsProvider, SysinfoProvider};
+use crate::sys::times::CpuTimeCounters;
+use crate::types::{ProcessInfo, ProcessSignal, Snapshot, Subsystems};
+
+// Shared context for everything that reads the machine. It owns the provider,
+// and with it the single sysinfo System, Disks and Networks, so callers only
+// choose which subsystems to refresh and get a snapshot back.
+pub struct Collector<P: MetricsProvider = SysinfoProvider> {
+    provider: P,
+    swap_counters: Option<SwapCounters>,
+    load_counters: Option<LoadCounters>,
+    time_counters: Option<CpuTimeCounters>,
+}
+
+impl Subsystems {
+    pub fn all() -> Self {
+        Subsystems {
+            cpu: true,
+            memory: true,
+            storage: true,
+            network: true,
+            processes: true,
+            system: true,
+            sensors: true,
+        }
+    }
+}
+
+impl Collector<SysinfoProvider> {
+    pub fn new() -> Self {
+        Self::with_provider(SysinfoProvider::new())
+    }
+}
+
+impl Default for Collector<SysinfoProvider> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: MetricsProvider> Collector<P> {
+    pub fn with_provider(mut provider: P) -> Self {
+        // Baseline for the first swap, context switch, interrupt and CPU time figures
+        let swap_counters = provider.read_swap_counters().ok();
+        let load_counters = provider.read_load_counters().ok();
+        let time_counters = provider.read_cpu_time_counters().ok();
+        Collector {
+            provider,
+            swap_counters,
+            load_counters,
+            time_counters,
+        }
+    }
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+    pub fn provider_mut(&mut self) -> &mut P {
+        &mut self.provider
+    }
+
+    // Reads only the requested subsystems. A subsystem that fails is left out of
+    // the snapshot and its error returned next to it, so one broken reader
+    // doesn't hide the others.
+    pub fn refresh(&mut self, subsystems: Subsystems) -> (Snapshot, Vec<MachineInfoError>) {
+        let mut errors = Vec::new();
+        let mut snapshot = Snapshot {
+            timestamp: unix_now(),
+            ..Snapshot::default()
+        };
+        if subsystems.cpu {
+            snapshot.cpu = keep(self.provider.cpu_info(), &mut errors);
+            if let Some(cpu) = snapshot.cpu.as_mut() {
+                let current = self.provider.read_load_counters().ok();
+                cpu.update_load(&mut self.load_counters, current);
+                let current = self.provider.read_cpu_time_counters().ok();
+                cpu.update_times(&mut self.time_counters, current);
+            }
+        }
+        if subsystems.memory {
+            snapshot.memory = keep(self.provider.memory_info(), &mut errors);
+            if let Some(memory) = snapshot.memory.as_mut() {
+                let current = self.provider.read_swap_counters().ok();
+                memory.update_swap_rates(&mut self.swap_counters, current);
+            }
+        }
+        if subsystems.storage {
+            snapshot.storage = keep(self.provider.storage_info(), &mut errors);
+        }
+        if subsystems.network {
+            snapshot.network = keep(self.provider.network_info(), &mut errors);
+        }
+        if subsystems.processes {
+            snapshot.processes = keep(self.provider.read_processes(), &mut errors);
+        }
+        if subsystems.system {
+            snapshot.system = keep(self.provider.read_system(), &mut errors);
+        }
+        if subsystems.sensors {
+            snapshot.sensors = keep(self.provider.read_sensors(), &mut errors);
+        }
+        (snapshot, errors)
+    }
+
+    pub fn send_signal(&mut self, target: &ProcessInfo, signal: ProcessSignal) -> Result<()> {
+        self.provider.send_signal(target, signal)
+    }
+}
+
+fn keep<T>(result: Result<T>, errors: &mut Vec<MachineInfoError>) -> Option<T> {
+    result.map_err(|e| errors.push(e)).ok()
+}
@@ -0,0 +1,118 @@
+use crate::error::{MachineInfoError, Result};
+use crate::sys::load::LoadCounters;
+use crate::sys::provider::{
+    CpuReading, DiskReading, MemoryReading, MetricsProvider, NetworkReading,
+};
+use crate::sys::times::CpuTimeCounters;
+use crate::types::{CpuFeatures, CpuTopology, ProcessInfo, Sensors, SystemInfo};
+
+// Provider that hands back fixed readings, for tests and demos that must not
+// depend on the machine they run on. Fill the fields with a fixture and every
+// read returns a copy of them.
+#[derive(Debug, Default, Clone)]
+pub struct FakeProvider {
+    pub cpus: Vec<CpuReading>,
+    pub memory: MemoryReading,
+    pub disks: Vec<DiskReading>,
+    pub networks: Vec<NetworkReading>,
+    pub processes: Vec<ProcessInfo>,
+    pub system: SystemInfo,
+    pub sensors: Sensors,
+    pub topology: Option<CpuTopology>,  // None reads as unsupported
+    pub features: Option<CpuFeatures>,  // None reads as unsupported
+    pub load: Option<LoadCounters>,     // None reads as unsupported
+    pub times: Option<CpuTimeCounters>, // None reads as unsupported
+}
+
+impl FakeProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_cpus(mut self, cpus: Vec<CpuReading>) -> Self {
+        self.cpus = cpus;
+        self
+    }
+    pub fn with_memory(mut self, memory: MemoryReading) -> Self {
+        self.memory = memory;
+        self
+    }
+    pub fn with_disks(mut self, disks: Vec<DiskReading>) -> Self {
+        self.disks = disks;
+        self
+    }
+    pub fn with_networks(mut self, networks: Vec<NetworkReading>) -> Self {
+        self.networks = networks;
+        self
+    }
+    pub fn with_processes(mut self, processes: Vec<ProcessInfo>) -> Self {
+        self.processes = processes;
+        self
+    }
+    pub fn with_system(mut self, system: SystemInfo) -> Self {
+        self.system = system;
+        self
+    }
+    pub fn with_sensors(mut self, sensors: Sensors) -> Self {
+        self.sensors = sensors;
+        self
+    }
+    pub fn with_topology(mut self, topology: CpuTopology) -> Self {
+        self.topology = Some(topology);
+        self
+    }
+    pub fn with_features(mut self, features: CpuFeatures) -> Self {
+        self.features = Some(features);
+        self
+    }
+    pub fn with_load(mut self, load: LoadCounters) -> Self {
+        self.load = Some(load);
+        self
+    }
+    pub fn with_times(mut self, times: CpuTimeCounters) -> Self {
+        self.times = Some(times);
+        self
+    }
+}
+
+impl MetricsProvider for FakeProvider {
+    fn read_cpus(&mut self) -> Result<Vec<CpuReading>> {
+        Ok(self.cpus.clone())
+    }
+    fn read_memory(&mut self) -> Result<MemoryReading> {
+        Ok(self.memory.clone())
+    }
+    fn read_disks(&mut self) -> Result<Vec<DiskReading>> {
+        Ok(self.disks.clone())
+    }
+    fn read_networks(&mut self) -> Result<Vec<NetworkReading>> {
+        Ok(self.networks.clone())
+    }
+    fn read_processes(&mut self) -> Result<Vec<ProcessInfo>> {
+        Ok(self.processes.clone())
+    }
+    fn read_system(&mut self) -> Result<SystemInfo> {
+        Ok(self.system.clone())
+    }
+    fn read_sensors(&mut self) -> Result<Sensors> {
+        Ok(self.sensors.clone())
+    }
+    fn read_cpu_topology(&mut self) -> Result<CpuTopology> {
+        self.topology
+            .clone()
+            .ok_or_else(|| MachineInfoError::Unsupported("CPU topology".to_string()))
+    }
+    fn read_cpu_features(&mut self) -> Result<CpuFeatures> {
+        self.features
+            .clone()
+            .ok_or_else(|| MachineInfoError::Unsupported("CPU features".to_string()))
+    }
+    fn read_load_counters(&mut self) -> Result<LoadCounters> {
+        self.load
+            .ok_or_else(|| MachineInfoError::Unsupported("Load average".to_string()))
+    }
+    fn read_cpu_time_counters(&mut self) -> Result<CpuTimeCounters> {
+        self.times
+            .clone()
+            .ok_or_else(|| MachineInfoError::Unsupported("CPU time breakdown".to_string()))
+    }
+}
@@ -0,0 +1,227 @@
+use crate::error::{MachineInfoError, Result};
+use crate::sys::topology::{CPUINFO_PATH, cpuinfo_field};
+use crate::types::CpuFeatures;
+use std::fs;
+
+// Flags each x86-64 microarchitecture level adds to the one below, in the kernel's
+// names (pni is SSE3, abm is LZCNT). Level 1 only needs long mode.
+const X86_64_LEVELS: [&[&str]; 3] = [
+    &[
+        "cx16", "lahf_lm", "popcnt", "pni", "sse4_1", "sse4_2", "ssse3",
+    ],
+    &[
+        "abm", "avx", "avx2", "bmi1", "bmi2", "f16c", "fma", "movbe", "xsave",
+    ],
+    &["avx512bw", "avx512cd", "avx512dq", "avx512f", "avx512vl"],
+];
+
+impl CpuFeatures {
+    // /proc/cpuinfo has the microcode revision and every flag the kernel knows,
+    // so it is preferred. CPUID and the standard library's feature detection
+    // cover the other platforms and sandboxes without /proc.
+    pub fn read() -> Result<Self> {
+        if cfg!(target_os = "linux")
+            && let Ok(text) = fs::read_to_string(CPUINFO_PATH)
+            && let Ok(features) = parse_cpuinfo(&text)
+        {
+            return Ok(features);
+        }
+        detect()
+    }
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|own| own.eq_ignore_ascii_case(flag))
+    }
+    // Flags containing `query` (case-insensitive), all of them for an empty query
+    pub fn search_flags(&self, query: &str) -> Vec<&str> {
+        let query = query.trim().to_lowercase();
+        self.flags
+            .iter()
+            .filter(|flag| flag.to_lowercase().contains(&query))
+            .map(String::as_str)
+            .collect()
+    }
+    // Highest x86-64 microarchitecture level (1-4) the flags cover, which decides
+    // whether a build for x86-64-v2, v3 or v4 runs here. None off x86-64.
+    pub fn x86_64_level(&self) -> Option<u8> {
+        if !self.has_flag("lm") {
+            return None;
+        }
+        let above_baseline = X86_64_LEVELS
+            .iter()
+            .take_while(|flags| flags.iter().all(|flag| self.has_flag(flag)))
+            .count();
+        Some(1 + above_baseline as u8)
+    }
+}
+
+// Reads the first processor block of /proc/cpuinfo. x86 names the fields
+// "cpu family", "model", "stepping" and "flags", ARM "CPU architecture",
+// "CPU part", "CPU revision" and "Features".
+pub fn parse_cpuinfo(text: &str) -> Result<CpuFeatures> {
+    for block in text.split("\n\n") {
+        let field = |names: [&str; 2]| names.iter().find_map(|name| cpuinfo_field(block, name));
+        let flags = field(["flags", "Features"]);
+        let family = field(["cpu family", "CPU architecture"]);
+        if flags.is_none() && family.is_none() {
+            continue;
+        }
+        let mut flags: Vec<String> = flags
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        flags.sort();
+        flags.dedup();
+        return Ok(CpuFeatures {
+            family: family.and_then(|value| parse_number(&value)),
+            model: field(["model", "CPU part"]).and_then(|value| parse_number(&value)),
+            stepping: field(["stepping", "CPU revision"]).and_then(|value| parse_number(&value)),
+            microcode: cpuinfo_field(block, "microcode"),
+            flags,
+        });
+    }
+    Err(MachineInfoError::NoData(
+        "no CPU identification in /proc/cpuinfo".to_string(),
+    ))
+}
+
+// Family, model and stepping from the EAX value of CPUID leaf 1. The extended
+// family only counts for family 15, the extended model for families 6 and 15.
+pub fn decode_cpuid_signature(eax: u32) -> (u32, u32, u32) {
+    let base_family = (eax >> 8) & 0xf;
+    let base_model = (eax >> 4) & 0xf;
+    let family = match base_family {
+        0xf => base_family + ((eax >> 20) & 0xff),
+        _ => base_family,
+    };
+    let model = match base_family {
+        0x6 | 0xf => base_model | (((eax >> 16) & 0xf) << 4),
+        _ => base_model,
+    };
+    (family, model, eax & 0xf)
+}
+
+// "143" on x86, "0xd0c" for ARM part numbers
+fn parse_number(value: &str) -> Option<u32> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn detect() -> Result<CpuFeatures> {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::__cpuid;
+
+    // Standard library feature names, then what the kernel calls them
+    macro_rules! detected {
+        ($($feature:tt => $flag:literal),* $(,)?) => {
+            [$((std::arch::is_x86_feature_detected!($feature), $flag)),*]
+        };
+    }
+    let detected = detected![
+        "adx" => "adx",
+        "aes" => "aes",
+        "avx" => "avx",
+        "avx2" => "avx2",
+        "avx512bw" => "avx512bw",
+        "avx512cd" => "avx512cd",
+        "avx512dq" => "avx512dq",
+        "avx512f" => "avx512f",
+        "avx512vl" => "avx512vl",
+        "avx512vnni" => "avx512_vnni",
+        "bmi1" => "bmi1",
+        "bmi2" => "bmi2",
+        "cmpxchg16b" => "cx16",
+        "f16c" => "f16c",
+        "fma" => "fma",
+        "lzcnt" => "abm",
+        "movbe" => "movbe",
+        "pclmulqdq" => "pclmulqdq",
+        "popcnt" => "popcnt",
+        "rdrand" => "rdrand",
+        "rdseed" => "rdseed",
+        "sha" => "sha_ni",
+        "sse" => "sse",
+        "sse2" => "sse2",
+        "sse3" => "pni",
+        "sse4.1" => "sse4_1",
+        "sse4.2" => "sse4_2",
+        "ssse3" => "ssse3",
+        "xsave" => "xsave",
+    ];
+    let mut flags: Vec<String> = detected
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, flag)| flag.to_string())
+        .collect();
+    // Long mode and LAHF/SAHF have no standard library name
+    if __cpuid(0x8000_0000).eax >= 0x8000_0001 {
+        let extended = __cpuid(0x8000_0001);
+        if extended.edx & (1 << 29) != 0 {
+            flags.push("lm".to_string());
+        }
+        if extended.ecx & 1 != 0 {
+            flags.push("lahf_lm".to_string());
+        }
+    }
+    flags.sort();
+
+    let (family, model, stepping) = decode_cpuid_signature(__cpuid(1).eax);
+    Ok(CpuFeatures {
+        family: Some(family),
+        model: Some(model),
+        stepping: Some(stepping),
+        microcode: None, // Only the kernel can read the microcode revision
+        flags,
+    })
+}
+
+#[cfg(target_arch = "aarch64")]
+fn detect() -> Result<CpuFeatures> {
+    macro_rules! detected {
+        ($($feature:tt => $flag:literal),* $(,)?) => {
+            [$((std::arch::is_aarch64_feature_detected!($feature), $flag)),*]
+        };
+    }
+    let detected = detected![
+        "aes" => "aes",
+        "crc" => "crc32",
+        "dotprod" => "asimddp",
+        "fp16" => "fphp",
+        "lse" => "atomics",
+        "neon" => "asimd",
+        "sha2" => "sha2",
+        "sve" => "sve",
+        "sve2" => "sve2",
+    ];
+    let mut flags: Vec<String> = detected
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, flag)| flag.to_string())
+        .collect();
+    flags.sort();
+    // The identification registers are privileged, only the kernel's copy of the
+    // architecture version can be trusted. There is none outside Linux.
+    let family = if cfg!(target_os = "linux") {
+        fs::read_to_string(CPUINFO_PATH)
+            .ok()
+            .and_then(|text| cpuinfo_field(&text, "CPU architecture"))
+            .and_then(|value| parse_number(&value))
+    } else {
+        None
+    };
+    Ok(CpuFeatures {
+        family,
+        flags,
+        ..Default::default()
+    })
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+fn detect() -> Result<CpuFeatures> {
+    Err(MachineInfoError::Unsupported("CPU features".to_string()))
+}
@@ -0,0 +1,131 @@
+use crate::error::Result;
+use crate::types::{CpuLoad, Processor};
+use std::time::Instant;
+
+// Declare Constants
+pub const LOADAVG_PATH: &str = "/proc/loadavg";
+pub const STAT_PATH: &str = "/proc/stat";
+
+// Load averages plus the cumulative /proc/stat counters; the per-second rates
+// are the difference between two readings
+#[derive(Debug, Clone, Copy)]
+pub struct LoadCounters {
+    pub load_average: [f64; 3], // 1, 5 and 15 minutes
+    pub running_processes: u64,
+    pub blocked_processes: u64,
+    pub context_switches: u64,
+    pub interrupts: u64,
+    pub at: Instant,
+}
+
+impl LoadCounters {
+    #[cfg(target_os = "linux")]
+    pub fn read() -> Result<Self> {
+        let loadavg = std::fs::read_to_string(LOADAVG_PATH)?;
+        let stat = std::fs::read_to_string(STAT_PATH)?;
+        Self::parse(&loadavg, &stat, Instant::now()).ok_or_else(|| {
+            crate::error::MachineInfoError::NoData(
+                "load counters in /proc/loadavg and /proc/stat".to_string(),
+            )
+        })
+    }
+    // The BSDs and macOS only have the averages, the counters stay at 0
+    #[cfg(all(unix, not(target_os = "linux")))]
+    pub fn read() -> Result<Self> {
+        let load = sysinfo::System::load_average();
+        Ok(LoadCounters {
+            load_average: [load.one, load.five, load.fifteen],
+            running_processes: 0,
+            blocked_processes: 0,
+            context_switches: 0,
+            interrupts: 0,
+            at: Instant::now(),
+        })
+    }
+    #[cfg(not(unix))]
+    pub fn read() -> Result<Self> {
+        Err(crate::error::MachineInfoError::Unsupported(
+            "Load average".to_string(),
+        ))
+    }
+    // The first three fields of /proc/loadavg, and ctxt, the total on the intr
+    // line, procs_running and procs_blocked from /proc/stat
+    pub fn parse(loadavg: &str, stat: &str, at: Instant) -> Option<Self> {
+        let averages: Vec<f64> = loadavg
+            .split_whitespace()
+            .take(3)
+            .filter_map(|value| value.parse().ok())
+            .collect();
+        let mut context_switches = None;
+        let mut interrupts = None;
+        let mut running_processes = None;
+        let mut blocked_processes = None;
+        for line in stat.lines() {
+            let mut fields = line.split_whitespace();
+            let value = |value: Option<&str>| value.and_then(|value| value.parse().ok());
+            match fields.next() {
+                Some("ctxt") => context_switches = value(fields.next()),
+                Some("intr") => interrupts = value(fields.next()),
+                Some("procs_running") => running_processes = value(fields.next()),
+                Some("procs_blocked") => blocked_processes = value(fields.next()),
+                _ => {}
+            }
+        }
+        Some(LoadCounters {
+            load_average: averages.try_into().ok()?,
+            running_processes: running_processes?,
+            blocked_processes: blocked_processes?,
+            context_switches: context_switches?,
+            interrupts: interrupts?,
+            at,
+        })
+    }
+}
+
+impl Processor {
+    // Fills the load from the counters read since the last call. The first call
+    // only stores a baseline and leaves the rates at 0.
+    pub fn update_load(
+        &mut self,
+        last_counters: &mut Option<LoadCounters>,
+        current: Option<LoadCounters>,
+    ) {
+        self.load = current.map(|current| {
+            let mut load = CpuLoad {
+                load_average_1m: current.load_average[0],
+                load_average_5m: current.load_average[1],
+                load_average_15m: current.load_average[2],
+                running_processes: current.running_processes,
+                blocked_processes: current.blocked_processes,
+                ..Default::default()
+            };
+            if let Some(previous) = last_counters.as_ref() {
+                let elapsed = current.at.duration_since(previous.at).as_secs_f64();
+                if elapsed > 0.0 {
+                    load.context_switches_per_sec = current
+                        .context_switches
+                        .saturating_sub(previous.context_switches)
+                        as f64
+                        / elapsed;
+                    load.interrupts_per_sec =
+                        current.interrupts.saturating_sub(previous.interrupts) as f64 / elapsed;
+                }
+            }
+            load
+        });
+        *last_counters = current;
+    }
+}
+
+impl CpuLoad {
+    // 1, 5 and 15 minute averages divided by the number of logical CPUs; above
+    // 1.0 tasks are queueing for a CPU
+    pub fn per_cpu(&self, logical_cpus: usize) -> [f64; 3] {
+        let cpus = logical_cpus.max(1) as f64;
+        [
+            self.load_average_1m / cpus,
+            self.load_average_5m / cpus,
+            self.load_average_15m / cpus,
+        ]
+    }
+}
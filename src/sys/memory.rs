@@ -1,33 +1,158 @@
-use crate::types::Memory;
+use crate::error::{MachineInfoError, Result};
+use crate::sys::provider::{self, MemoryReading};
+use crate::types::{Memory, MemorySegment};
+use std::time::Instant;
 use sysinfo::System;
 
+// Cumulative swap page counters; rates are the difference between two readings
+#[derive(Debug, Clone, Copy)]
+pub struct SwapCounters {
+    pub pages_in: u64,
+    pub pages_out: u64,
+    pub at: Instant,
+}
+
+impl SwapCounters {
+    #[cfg(target_os = "linux")]
+    pub fn read() -> Result<Self> {
+        let vmstat = std::fs::read_to_string("/proc/vmstat")?;
+        Self::parse(&vmstat, Instant::now())
+            .ok_or_else(|| MachineInfoError::NoData("swap counters in /proc/vmstat".to_string()))
+    }
+    #[cfg(not(target_os = "linux"))]
+    pub fn read() -> Result<Self> {
+        Err(MachineInfoError::Unsupported("Swap activity".to_string()))
+    }
+    // Picks pswpin / pswpout out of /proc/vmstat
+    pub fn parse(vmstat: &str, at: Instant) -> Option<Self> {
+        let mut pages_in = None;
+        let mut pages_out = None;
+        for line in vmstat.lines() {
+            let mut fields = line.split_whitespace();
+            match (fields.next(), fields.next()) {
+                (Some("pswpin"), Some(value)) => pages_in = value.parse().ok(),
+                (Some("pswpout"), Some(value)) => pages_out = value.parse().ok(),
+                _ => {}
+            }
+        }
+        Some(SwapCounters {
+            pages_in: pages_in?,
+            pages_out: pages_out?,
+            at,
+        })
+    }
+}
+
 impl Memory {
     pub fn set_memory_connection() -> System {
-        let mut _running_system = System::new_all();
+        let mut _running_system = System::new();
         _running_system.refresh_memory();
         _running_system
     }
-    pub fn get_memory_info(_passed_system: &mut System) -> Memory {
-        let _running_system = _passed_system;
-        // Define Constants
-        const BASE_VALUE: u32 = 1024;
-        const RAISED_VALUE: u32 = 3;
-        const _BYTES_TO_GB: f64 = BASE_VALUE.pow(RAISED_VALUE) as f64;
+    pub fn get_memory_info(_passed_system: &mut System) -> Result<Memory> {
+        let _reading = provider::read_memory(_passed_system)?;
+        Self::from_reading(&_reading)
+    }
+    pub fn from_reading(_reading: &MemoryReading) -> Result<Memory> {
+        if _reading.total_bytes == 0 {
+            return Err(MachineInfoError::NoData("total memory".to_string()));
+        }
+
         // Declare Variables
         let mut _my_memory = Memory::default();
-        // Refresh memory
-        _running_system.refresh_memory();
 
-        let mut _temp_total = _running_system.total_memory() as f64 / _BYTES_TO_GB;
-        let _temp_free = _running_system.available_memory() as f64 / _BYTES_TO_GB;
-        let _temp_used = _temp_total - _temp_free;
+        let _temp_total = _reading.total_bytes;
+        let _temp_free = _reading.available_bytes;
+        let _temp_used = _temp_total.saturating_sub(_temp_free);
 
         // Pack the struct
-        _my_memory.total = format!("{:.2} GB", _temp_total).into();
-        _my_memory.used = format!("{:.2} GB", _temp_used).into();
-        _my_memory.free = format!("{:.2} GB", _temp_free).into();
+        _my_memory.total_bytes = _temp_total;
+        _my_memory.used_bytes = _temp_used;
+        _my_memory.free_bytes = _temp_free;
+        _my_memory.swap_total_bytes = _reading.swap_total_bytes;
+        _my_memory.swap_used_bytes = _reading.swap_used_bytes;
+        _my_memory.swap_free_bytes = _reading.swap_free_bytes;
+
+        // Add the kernel's own breakdown where it is available
+        if let Some(meminfo) = &_reading.meminfo {
+            _my_memory.apply_meminfo(meminfo);
+        }
 
         // Return Memory Info
-        _my_memory
+        Ok(_my_memory)
+    }
+    // Fills the breakdown fields from the contents of /proc/meminfo (values in kB)
+    pub fn apply_meminfo(&mut self, meminfo: &str) {
+        for line in meminfo.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let Some(kib) = value
+                .split_whitespace()
+                .next()
+                .and_then(|kib| kib.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            let bytes = kib * 1024;
+            match key.trim() {
+                "MemFree" => self.unused_bytes = bytes,
+                "Buffers" => self.buffers_bytes = bytes,
+                "Cached" => self.cached_bytes = bytes,
+                "Shmem" => self.shared_bytes = bytes,
+                "SReclaimable" => self.slab_reclaimable_bytes = bytes,
+                "SUnreclaim" => self.slab_unreclaimable_bytes = bytes,
+                "Dirty" => self.dirty_bytes = bytes,
+                "Writeback" => self.writeback_bytes = bytes,
+                _ => {}
+            }
+        }
+    }
+    // Splits total memory into parts that add up to total_bytes, in display order.
+    // Without a breakdown this is just used and free.
+    pub fn get_segments(&self) -> Vec<MemorySegment> {
+        let segment = |label: &str, bytes: u64| MemorySegment {
+            label: label.to_string(),
+            bytes,
+        };
+        if self.unused_bytes == 0 && self.cached_bytes == 0 {
+            return vec![
+                segment("Used", self.used_bytes),
+                segment("Free", self.free_bytes),
+            ];
+        }
+        // Same split as `free`: whatever isn't free, buffers, cache or reclaimable slab
+        let cache = self.cached_bytes + self.slab_reclaimable_bytes;
+        let applications = self
+            .total_bytes
+            .saturating_sub(self.unused_bytes)
+            .saturating_sub(self.buffers_bytes)
+            .saturating_sub(cache)
+            .saturating_sub(self.slab_unreclaimable_bytes);
+        vec![
+            segment("Applications", applications),
+            segment("Kernel", self.slab_unreclaimable_bytes),
+            segment("Buffers", self.buffers_bytes),
+            segment("Cache", cache),
+            segment("Free", self.unused_bytes),
+        ]
+    }
+    // Fills the swap-in/out rates from the counters read since the last call.
+    // The first call only stores a baseline and leaves the rates at 0.
+    pub fn update_swap_rates(
+        &mut self,
+        last_counters: &mut Option<SwapCounters>,
+        current: Option<SwapCounters>,
+    ) {
+        if let (Some(previous), Some(current)) = (last_counters.as_ref(), current.as_ref()) {
+            let elapsed = current.at.duration_since(previous.at).as_secs_f64();
+            if elapsed > 0.0 {
+                self.swap_in_pages_per_sec =
+                    current.pages_in.saturating_sub(previous.pages_in) as f64 / elapsed;
+                self.swap_out_pages_per_sec =
+                    current.pages_out.saturating_sub(previous.pages_out) as f64 / elapsed;
+            }
+        }
+        *last_counters = current;
     }
 }
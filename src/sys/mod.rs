@@ -1,3 +1,14 @@
+pub mod collector;
+pub mod fake;
+pub mod features;
+pub mod load;
 pub mod memory;
+pub mod network;
+pub mod process;
 pub mod processor;
+pub mod provider;
+pub mod sensors;
 pub mod storage;
+pub mod system;
+pub mod times;
+pub mod topology;
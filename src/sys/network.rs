@@ -0,0 +1,72 @@
+use crate::error::Result;
+use crate::sys::provider::{self, NetworkReading};
+use crate::types::Network;
+use std::time::Instant;
+use sysinfo::Networks;
+
+// Rates need the time between two refreshes, so the connection remembers it
+pub struct NetworkConnection {
+    pub(crate) networks: Networks,
+    pub(crate) last_refresh: Instant,
+}
+
+impl Network {
+    pub fn get_network_connection() -> NetworkConnection {
+        NetworkConnection {
+            networks: Networks::new_with_refreshed_list(),
+            last_refresh: Instant::now(),
+        }
+    }
+    pub fn get_network_info(passed_connection: &mut NetworkConnection) -> Result<Vec<Self>> {
+        // Pack one struct per interface, sorted so the list doesn't jump around
+        let mut my_networks: Vec<Self> = provider::read_networks(passed_connection)?
+            .iter()
+            .map(Self::from_reading)
+            .collect();
+        my_networks.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(my_networks)
+    }
+    pub fn from_reading(reading: &NetworkReading) -> Self {
+        // Declare Variables
+        let mut my_network = Self::default();
+        let per_second = |bytes: u64| {
+            if reading.elapsed_secs > 0.0 {
+                bytes as f64 / reading.elapsed_secs
+            } else {
+                0.0
+            }
+        };
+
+        my_network.name = Some(reading.name.clone());
+        my_network.mac_address = reading.mac_address.clone();
+        my_network.ipv4_addresses = reading.ipv4_addresses.clone();
+        my_network.ipv6_addresses = reading.ipv6_addresses.clone();
+        my_network.mtu = reading.mtu;
+        my_network.link_state = reading.link_state.clone();
+        my_network.rx_bytes_per_sec = per_second(reading.received_bytes);
+        my_network.tx_bytes_per_sec = per_second(reading.transmitted_bytes);
+        my_network.rx_packets = reading.received_packets;
+        my_network.tx_packets = reading.transmitted_packets;
+        my_network.rx_errors = reading.received_errors;
+        my_network.tx_errors = reading.transmitted_errors;
+        my_network.total_rx_bytes = reading.total_received_bytes;
+        my_network.total_tx_bytes = reading.total_transmitted_bytes;
+        my_network.total_rx_packets = reading.total_received_packets;
+        my_network.total_tx_packets = reading.total_transmitted_packets;
+        my_network.total_rx_errors = reading.total_received_errors;
+        my_network.total_tx_errors = reading.total_transmitted_errors;
+
+        // Return a packed struct
+        my_network
+    }
+    #[cfg(target_os = "linux")]
+    pub(crate) fn get_link_state(name: &str) -> Option<String> {
+        std::fs::read_to_string(format!("/sys/class/net/{}/operstate", name))
+            .ok()
+            .map(|state| state.trim().to_string())
+    }
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn get_link_state(_name: &str) -> Option<String> {
+        None
+    }
+}
@@ -0,0 +1,157 @@
+use crate::error::{MachineInfoError, Result};
+use crate::types::{ProcessInfo, ProcessSignal, ProcessSortKey};
+use std::cmp::Ordering;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, Signal, System, UpdateKind, Users};
+
+impl ProcessSortKey {
+    // Matches the column order of the process table in the UI
+    pub fn from_column(column: i32) -> Self {
+        match column {
+            1 => ProcessSortKey::Name,
+            2 => ProcessSortKey::User,
+            3 => ProcessSortKey::Cpu,
+            4 => ProcessSortKey::Rss,
+            5 => ProcessSortKey::Virtual,
+            6 => ProcessSortKey::StartTime,
+            7 => ProcessSortKey::Command,
+            _ => ProcessSortKey::Pid,
+        }
+    }
+}
+
+impl ProcessInfo {
+    pub fn get_user_connection() -> Users {
+        Users::new_with_refreshed_list()
+    }
+    pub fn get_process_info(
+        _passed_system: &mut System,
+        _passed_users: &Users,
+    ) -> Vec<ProcessInfo> {
+        let _running_system = _passed_system;
+        // Only refresh what the table shows, command lines and owners rarely change
+        _running_system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_cpu()
+                .with_memory()
+                .with_user(UpdateKind::OnlyIfNotSet)
+                .with_cmd(UpdateKind::OnlyIfNotSet),
+        );
+
+        // Pack one struct per process
+        _running_system
+            .processes()
+            .values()
+            .map(|process| {
+                let command_line = process
+                    .cmd()
+                    .iter()
+                    .map(|arg| arg.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                ProcessInfo {
+                    pid: process.pid().as_u32(),
+                    name: Some(process.name().to_string_lossy().into_owned()),
+                    user: process
+                        .user_id()
+                        .and_then(|uid| _passed_users.get_user_by_id(uid))
+                        .map(|user| user.name().to_string()),
+                    cpu_usage_percent: process.cpu_usage(),
+                    rss_bytes: process.memory(),
+                    virtual_bytes: process.virtual_memory(),
+                    start_time: process.start_time(),
+                    command_line: (!command_line.is_empty()).then_some(command_line),
+                }
+            })
+            .collect()
+    }
+
+    // Keeps processes whose pid, name, user or command line contain the filter (case-insensitive)
+    pub fn filter(processes: &[ProcessInfo], filter: &str) -> Vec<ProcessInfo> {
+        let filter = filter.trim().to_lowercase();
+        if filter.is_empty() {
+            return processes.to_vec();
+        }
+        let contains = |value: &Option<String>| {
+            value
+                .as_deref()
+                .is_some_and(|value| value.to_lowercase().contains(&filter))
+        };
+        processes
+            .iter()
+            .filter(|process| {
+                process.pid.to_string().contains(&filter)
+                    || contains(&process.name)
+                    || contains(&process.user)
+                    || contains(&process.command_line)
+            })
+            .cloned()
+            .collect()
+    }
+    pub fn sort(processes: &mut [ProcessInfo], key: ProcessSortKey, ascending: bool) {
+        processes.sort_by(|a, b| {
+            let ordering = match key {
+                ProcessSortKey::Pid => a.pid.cmp(&b.pid),
+                ProcessSortKey::Name => a.name.cmp(&b.name),
+                ProcessSortKey::User => a.user.cmp(&b.user),
+                ProcessSortKey::Cpu => a
+                    .cpu_usage_percent
+                    .partial_cmp(&b.cpu_usage_percent)
+                    .unwrap_or(Ordering::Equal),
+                ProcessSortKey::Rss => a.rss_bytes.cmp(&b.rss_bytes),
+                ProcessSortKey::Virtual => a.virtual_bytes.cmp(&b.virtual_bytes),
+                ProcessSortKey::StartTime => a.start_time.cmp(&b.start_time),
+                ProcessSortKey::Command => a.command_line.cmp(&b.command_line),
+            }
+            // Fall back to pid so equal rows keep a stable order between refreshes
+            .then(a.pid.cmp(&b.pid));
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+    }
+
+    // The table can be one refresh old, and by then its pid may belong to another
+    // process. Same name and start time means it is still the one that was shown.
+    pub fn is_same_process(&self, name: &str, start_time: u64) -> bool {
+        self.name.as_deref() == Some(name) && self.start_time == start_time
+    }
+
+    // Signals `target`, a row of the process table, after checking its pid wasn't reused
+    pub fn send_signal(
+        _passed_system: &mut System,
+        target: &ProcessInfo,
+        signal: ProcessSignal,
+    ) -> Result<()> {
+        let pid = target.pid;
+        _passed_system.refresh_processes(ProcessesToUpdate::Some(&[Pid::from_u32(pid)]), true);
+        let Some(process) = _passed_system.process(Pid::from_u32(pid)) else {
+            return Err(MachineInfoError::NoData(format!(
+                "process {} no longer exists",
+                pid
+            )));
+        };
+        if !target.is_same_process(&process.name().to_string_lossy(), process.start_time()) {
+            return Err(MachineInfoError::NoData(format!(
+                "process {} is no longer {}, refresh and try again",
+                pid,
+                target.name.as_deref().unwrap_or("the selected process")
+            )));
+        }
+        let signal = match signal {
+            ProcessSignal::Terminate => Signal::Term,
+            ProcessSignal::Kill => Signal::Kill,
+        };
+        match process.kill_with(signal) {
+            Some(true) => Ok(()),
+            Some(false) => Err(MachineInfoError::PermissionDenied(format!(
+                "unable to send {:?} to process {}",
+                signal, pid
+            ))),
+            None => Err(MachineInfoError::Unsupported(format!("{:?}", signal))),
+        }
+    }
+}
@@ -1,91 +1,58 @@
-use crate::types::Processor;
+use crate::error::{MachineInfoError, Result};
+use crate::sys::provider::{self, CpuReading};
+use crate::sys::system;
+use crate::types::{Processor, ProcessorCore};
 use sysinfo::System;
 impl Processor {
     pub fn set_cpu_connection() -> System {
-        let mut _running_system = System::new_all();
+        let mut _running_system = System::new();
         _running_system.refresh_cpu_all();
         _running_system
     }
-    pub fn get_cpu_info(_passed_system: &mut System) -> Processor {
-        // Create a vector to store the percentages of each core
-        let mut _core_percents: Vec<f32> = Vec::new();
-        // Create a reference to the passed system
-        let _running_system = _passed_system;
-        // Declare Constants
-        const _MHZ_TO_GHZ: f64 = 1000.0;
+    pub fn get_cpu_info(_passed_system: &mut System) -> Result<Processor> {
+        // Read every core and let the shared math do the rest
+        let _readings = provider::read_cpus(_passed_system)?;
+        Self::from_readings(&_readings)
+    }
+    // Builds the processor summary from one reading per core
+    pub fn from_readings(_readings: &[CpuReading]) -> Result<Processor> {
+        // Name and speed come from the first core, so there has to be one
+        let Some(_my_cpu) = _readings.first() else {
+            return Err(MachineInfoError::NoData("no CPUs reported".to_string()));
+        };
         // Declare Variables
-        let mut _cpu_count = 0;
         let mut _my_processor = Processor::default();
 
-        // Define CPU Info
-        _running_system.refresh_cpu_all();
-        let _my_cpu = _running_system.cpus().first().unwrap();
-
-        // Count the number of cores and get the total % usage
-        for _cpu in _running_system.cpus() {
-            _cpu_count += 1;
-            _core_percents.push(_cpu.cpu_usage())
-        }
-        let _temp_usage: f64 = _core_percents.iter().sum::<f32>() as f64 / _cpu_count as f64;
-
-        // Get speed in Ghz
-        // cast frequency into a float
-        let _temp_freq: f64 = _my_cpu.frequency() as f64 / _MHZ_TO_GHZ;
+        // Collect usage and frequency of every core
+        let _per_core: Vec<ProcessorCore> = _readings
+            .iter()
+            .enumerate()
+            .map(|(index, cpu)| ProcessorCore {
+                index,
+                usage_percent: cpu.usage_percent,
+                frequency_mhz: cpu.frequency_mhz,
+                times: None, // Filled in from /proc/stat by the collector
+            })
+            .collect();
+        let _cpu_count = _per_core.len();
+        let _temp_usage: f32 =
+            _per_core.iter().map(|core| core.usage_percent).sum::<f32>() / _cpu_count as f32;
 
         // Pack Struct
-        _my_processor.name = format!("{}", _my_cpu.brand()).into();
-        _my_processor.vendor = format!("{}", _my_cpu.vendor_id()).into();
-        _my_processor.cores = format!("{}", _cpu_count).into();
-        _my_processor.family = format!("{}", Self::get_cpu_architecture()).into();
-        _my_processor.speed = format!("{:.2} GHz", _temp_freq).into();
-        _my_processor.usage = format!("{:.2} %", _temp_usage).into();
+        _my_processor.name = Some(_my_cpu.brand.clone());
+        _my_processor.vendor = Some(_my_cpu.vendor_id.clone());
+        _my_processor.frequency_mhz = _my_cpu.frequency_mhz;
+        _my_processor.cores = _cpu_count;
+        _my_processor.architecture = Some(Self::get_cpu_architecture());
+        _my_processor.usage_percent = _temp_usage;
+        _my_processor.per_core = _per_core;
 
         // Return Processor Info
-        _my_processor
+        Ok(_my_processor)
     }
-    #[allow(unreachable_code)]
+    // What the machine is, not what this binary was built for, so a 32-bit x86
+    // build on a 64-bit kernel says x86_64 and an x86_64 build under Rosetta arm64
     fn get_cpu_architecture() -> String {
-        #[cfg(any(target_arch = "x86"))]
-        {
-            return "x86".to_string();
-        }
-        #[cfg(any(target_arch = "x86_64"))]
-        {
-            return "x86_64".to_string();
-        }
-        #[cfg(any(target_arch = "aarch64"))]
-        {
-            return "aarch64".to_string();
-        }
-        #[cfg(any(target_arch = "arm"))]
-        {
-            return "arm".to_string();
-        }
-        #[cfg(any(target_arch = "riscv32"))]
-        {
-            return "riscv32".to_string();
-        }
-        #[cfg(any(target_arch = "riscv64"))]
-        {
-            return "riscv64".to_string();
-        }
-        #[cfg(any(target_arch = "powerpc"))]
-        {
-            return "powerpc".to_string();
-        }
-        #[cfg(any(target_arch = "powerpc64"))]
-        {
-            return "powerpc64".to_string();
-        }
-        #[cfg(any(target_arch = "mips"))]
-        {
-            return "mips".to_string();
-        }
-        #[cfg(any(target_arch = "mips64"))]
-        {
-            return "mips64".to_string();
-        }
-        //TODO add more architectures
-        "Unknown Architecture".to_string()
+        system::host_architecture().unwrap_or_else(|| std::env::consts::ARCH.to_string())
     }
 }
@@ -0,0 +1,311 @@
+use crate::error::{MachineInfoError, Result};
+use crate::sys::load::LoadCounters;
+use crate::sys::memory::SwapCounters;
+use crate::sys::network::NetworkConnection;
+use crate::sys::times::CpuTimeCounters;
+use crate::types::{
+    CpuFeatures, CpuTopology, Memory, Network, ProcessInfo, ProcessSignal, Processor, Sensors,
+    Storage, SystemInfo,
+};
+use std::time::Instant;
+use sysinfo::{Components, Disks, IpNetwork, System, Users};
+
+// Raw readings as the operating system reports them. All math that turns them
+// into `Processor`, `Memory`, `Storage` and `Network` lives in `sys`, so any
+// provider, real or fake, goes through the same code.
+
+#[derive(Debug, Default, Clone)]
+pub struct CpuReading {
+    pub brand: String,
+    pub vendor_id: String,
+    pub usage_percent: f32,
+    pub frequency_mhz: u64,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MemoryReading {
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    pub swap_total_bytes: u64,
+    pub swap_used_bytes: u64,
+    pub swap_free_bytes: u64,
+    pub meminfo: Option<String>, // Contents of /proc/meminfo where available
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct DiskReading {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub kind: String,
+    pub is_removable: bool,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct NetworkReading {
+    pub name: String,
+    pub mac_address: Option<String>,
+    pub ipv4_addresses: Vec<String>,
+    pub ipv6_addresses: Vec<String>,
+    pub mtu: u64,
+    pub link_state: Option<String>,
+    pub elapsed_secs: f64, // Time covered by the counters below
+    pub received_bytes: u64,
+    pub transmitted_bytes: u64,
+    pub received_packets: u64,
+    pub transmitted_packets: u64,
+    pub received_errors: u64,
+    pub transmitted_errors: u64,
+    pub total_received_bytes: u64,
+    pub total_transmitted_bytes: u64,
+    pub total_received_packets: u64,
+    pub total_transmitted_packets: u64,
+    pub total_received_errors: u64,
+    pub total_transmitted_errors: u64,
+}
+
+// Source of the raw readings. Every call refreshes what it reads.
+pub trait MetricsProvider {
+    fn read_cpus(&mut self) -> Result<Vec<CpuReading>>;
+    fn read_memory(&mut self) -> Result<MemoryReading>;
+    fn read_disks(&mut self) -> Result<Vec<DiskReading>>;
+    fn read_networks(&mut self) -> Result<Vec<NetworkReading>>;
+    fn read_processes(&mut self) -> Result<Vec<ProcessInfo>>;
+    fn read_system(&mut self) -> Result<SystemInfo>;
+    fn read_sensors(&mut self) -> Result<Sensors>;
+
+    // Optional capabilities, unsupported unless a provider says otherwise
+    fn read_swap_counters(&mut self) -> Result<SwapCounters> {
+        Err(MachineInfoError::Unsupported("Swap activity".to_string()))
+    }
+    fn read_load_counters(&mut self) -> Result<LoadCounters> {
+        Err(MachineInfoError::Unsupported("Load average".to_string()))
+    }
+    fn read_cpu_time_counters(&mut self) -> Result<CpuTimeCounters> {
+        Err(MachineInfoError::Unsupported("CPU time breakdown".to_string()))
+    }
+    fn send_signal(&mut self, _target: &ProcessInfo, signal: ProcessSignal) -> Result<()> {
+        Err(MachineInfoError::Unsupported(format!("{:?}", signal)))
+    }
+    fn read_cpu_topology(&mut self) -> Result<CpuTopology> {
+        Err(MachineInfoError::Unsupported("CPU topology".to_string()))
+    }
+    fn read_cpu_features(&mut self) -> Result<CpuFeatures> {
+        Err(MachineInfoError::Unsupported("CPU features".to_string()))
+    }
+
+    fn cpu_info(&mut self) -> Result<Processor> {
+        let mut processor = Processor::from_readings(&self.read_cpus()?)?;
+        // The usage figures are complete without it, so a missing topology is no error
+        processor.topology = self.read_cpu_topology().ok();
+        processor.features = self.read_cpu_features().ok();
+        Ok(processor)
+    }
+    fn memory_info(&mut self) -> Result<Memory> {
+        Memory::from_reading(&self.read_memory()?)
+    }
+    fn storage_info(&mut self) -> Result<Vec<Storage>> {
+        Ok(self
+            .read_disks()?
+            .iter()
+            .map(Storage::from_reading)
+            .collect())
+    }
+    fn network_info(&mut self) -> Result<Vec<Network>> {
+        let mut my_networks: Vec<Network> = self
+            .read_networks()?
+            .iter()
+            .map(Network::from_reading)
+            .collect();
+        // Sorted so the list doesn't jump around
+        my_networks.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(my_networks)
+    }
+}
+
+// Default provider backed by sysinfo. One System serves CPU, memory and processes.
+pub struct SysinfoProvider {
+    system: System,
+    disks: Disks,
+    networks: NetworkConnection,
+    components: Components,        // Listed on the first sensor reading
+    users: Option<Users>,          // Loaded with the first process list
+    topology: Option<CpuTopology>, // Read once, CPUs rarely come and go
+    features: Option<CpuFeatures>, // Read once, they only change with a microcode update
+}
+
+impl SysinfoProvider {
+    pub fn new() -> Self {
+        // Take a first CPU and network reading so the next one has something to compare to
+        let mut system = System::new();
+        system.refresh_cpu_all();
+        SysinfoProvider {
+            system,
+            disks: Disks::new_with_refreshed_list(),
+            networks: Network::get_network_connection(),
+            components: Components::new(),
+            users: None,
+            topology: None,
+            features: None,
+        }
+    }
+}
+
+impl Default for SysinfoProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsProvider for SysinfoProvider {
+    fn read_cpus(&mut self) -> Result<Vec<CpuReading>> {
+        read_cpus(&mut self.system)
+    }
+    fn read_memory(&mut self) -> Result<MemoryReading> {
+        read_memory(&mut self.system)
+    }
+    fn read_disks(&mut self) -> Result<Vec<DiskReading>> {
+        read_disks(&mut self.disks)
+    }
+    fn read_networks(&mut self) -> Result<Vec<NetworkReading>> {
+        read_networks(&mut self.networks)
+    }
+    fn read_processes(&mut self) -> Result<Vec<ProcessInfo>> {
+        let users = self
+            .users
+            .get_or_insert_with(ProcessInfo::get_user_connection);
+        Ok(ProcessInfo::get_process_info(&mut self.system, users))
+    }
+    fn read_system(&mut self) -> Result<SystemInfo> {
+        SystemInfo::read()
+    }
+    fn read_sensors(&mut self) -> Result<Sensors> {
+        Sensors::read(&mut self.components)
+    }
+    fn read_swap_counters(&mut self) -> Result<SwapCounters> {
+        SwapCounters::read()
+    }
+    fn read_load_counters(&mut self) -> Result<LoadCounters> {
+        LoadCounters::read()
+    }
+    fn read_cpu_time_counters(&mut self) -> Result<CpuTimeCounters> {
+        CpuTimeCounters::read()
+    }
+    fn send_signal(&mut self, target: &ProcessInfo, signal: ProcessSignal) -> Result<()> {
+        ProcessInfo::send_signal(&mut self.system, target, signal)
+    }
+    fn read_cpu_topology(&mut self) -> Result<CpuTopology> {
+        if let Some(topology) = &self.topology {
+            return Ok(topology.clone());
+        }
+        let topology = CpuTopology::read()?;
+        self.topology = Some(topology.clone());
+        Ok(topology)
+    }
+    fn read_cpu_features(&mut self) -> Result<CpuFeatures> {
+        if let Some(features) = &self.features {
+            return Ok(features.clone());
+        }
+        let features = CpuFeatures::read()?;
+        self.features = Some(features.clone());
+        Ok(features)
+    }
+}
+
+// The readers below are shared with the `get_*_info` connection functions
+
+pub fn read_cpus(system: &mut System) -> Result<Vec<CpuReading>> {
+    system.refresh_cpu_all();
+    Ok(system
+        .cpus()
+        .iter()
+        .map(|cpu| CpuReading {
+            brand: cpu.brand().to_string(),
+            vendor_id: cpu.vendor_id().to_string(),
+            usage_percent: cpu.cpu_usage(),
+            frequency_mhz: cpu.frequency(),
+        })
+        .collect())
+}
+
+pub fn read_memory(system: &mut System) -> Result<MemoryReading> {
+    system.refresh_memory();
+    Ok(MemoryReading {
+        total_bytes: system.total_memory(),
+        available_bytes: system.available_memory(),
+        swap_total_bytes: system.total_swap(),
+        swap_used_bytes: system.used_swap(),
+        swap_free_bytes: system.free_swap(),
+        meminfo: read_meminfo(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn read_meminfo() -> Option<String> {
+    std::fs::read_to_string("/proc/meminfo").ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_meminfo() -> Option<String> {
+    None
+}
+
+pub fn read_disks(disks: &mut Disks) -> Result<Vec<DiskReading>> {
+    // Refresh the disk list so newly mounted drives show up
+    disks.refresh(true);
+    Ok(disks
+        .iter()
+        .map(|disk| DiskReading {
+            name: disk.name().to_string_lossy().into_owned(),
+            mount_point: disk.mount_point().display().to_string(),
+            file_system: disk.file_system().to_string_lossy().into_owned(),
+            kind: disk.kind().to_string(),
+            is_removable: disk.is_removable(),
+            total_bytes: disk.total_space(),
+            available_bytes: disk.available_space(),
+        })
+        .collect())
+}
+
+pub fn read_networks(connection: &mut NetworkConnection) -> Result<Vec<NetworkReading>> {
+    // Refresh the counters and pick up new interfaces
+    connection.networks.refresh(true);
+    let now = Instant::now();
+    let elapsed_secs = now.duration_since(connection.last_refresh).as_secs_f64();
+    connection.last_refresh = now;
+
+    Ok(connection
+        .networks
+        .iter()
+        .map(|(name, data)| {
+            let mac_address = data.mac_address();
+            let (ipv4, ipv6): (Vec<&IpNetwork>, Vec<&IpNetwork>) = data
+                .ip_networks()
+                .iter()
+                .partition(|network| network.addr.is_ipv4());
+            NetworkReading {
+                name: name.to_string(),
+                mac_address: (!mac_address.is_unspecified()).then(|| mac_address.to_string()),
+                ipv4_addresses: ipv4.iter().map(|network| network.to_string()).collect(),
+                ipv6_addresses: ipv6.iter().map(|network| network.to_string()).collect(),
+                mtu: data.mtu(),
+                link_state: Network::get_link_state(name),
+                elapsed_secs,
+                received_bytes: data.received(),
+                transmitted_bytes: data.transmitted(),
+                received_packets: data.packets_received(),
+                transmitted_packets: data.packets_transmitted(),
+                received_errors: data.errors_on_received(),
+                transmitted_errors: data.errors_on_transmitted(),
+                total_received_bytes: data.total_received(),
+                total_transmitted_bytes: data.total_transmitted(),
+                total_received_packets: data.total_packets_received(),
+                total_transmitted_packets: data.total_packets_transmitted(),
+                total_received_errors: data.total_errors_on_received(),
+                total_transmitted_errors: data.total_errors_on_transmitted(),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,168 @@
+use crate::error::Result;
+use crate::types::{Fan, Sensors, Temperature};
+use std::fs;
+use std::path::Path;
+use sysinfo::Components;
+
+// Declare Constants
+pub const HWMON_ROOT: &str = "/sys/class/hwmon";
+const MILLIDEGREES: f32 = 1000.0;
+
+impl Sensors {
+    // hwmon on Linux has fans and the trip points sysinfo leaves out, so it is
+    // preferred whenever it reports anything. sysinfo covers the other platforms.
+    pub fn read(components: &mut Components) -> Result<Self> {
+        if cfg!(target_os = "linux") {
+            let sensors = read_hwmon(Path::new(HWMON_ROOT))?;
+            if !sensors.temperatures.is_empty() || !sensors.fans.is_empty() {
+                return Ok(sensors);
+            }
+        }
+        Ok(Self::from_components(components))
+    }
+    pub fn from_components(components: &mut Components) -> Self {
+        // Sensors can come and go, e.g. when an NVMe drive powers down
+        components.refresh(true);
+        let temperatures = components
+            .iter()
+            .filter_map(|component| {
+                Some(Temperature {
+                    label: component.label().to_string(),
+                    chip: String::new(),
+                    current_celsius: component.temperature().filter(|t| t.is_finite())?,
+                    max_celsius: component.max().filter(|t| t.is_finite()),
+                    critical_celsius: component.critical().filter(|t| t.is_finite()),
+                })
+            })
+            .collect();
+        Sensors {
+            temperatures,
+            fans: Vec::new(),
+        }
+    }
+    // Hottest sensor, what the CPU page leads with
+    pub fn hottest(&self) -> Option<&Temperature> {
+        self.temperatures
+            .iter()
+            .max_by(|a, b| a.current_celsius.total_cmp(&b.current_celsius))
+    }
+}
+
+impl Temperature {
+    // True once the sensor reached the temperature the hardware throttles at
+    pub fn is_throttling(&self) -> bool {
+        self.max_celsius
+            .is_some_and(|max| self.current_celsius >= max)
+    }
+}
+
+// Reads every chip below `root` (normally /sys/class/hwmon). Each chip is a
+// directory with a `name` file and tempN_* / fanN_* attributes, see the kernel's
+// hwmon sysfs interface. A missing root means no sensors, not an error.
+pub fn read_hwmon(root: &Path) -> Result<Sensors> {
+    let mut sensors = Sensors::default();
+    let mut chips: Vec<_> = match fs::read_dir(root) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(sensors),
+        Err(e) => return Err(e.into()),
+    };
+    chips.sort_by_key(|path| natural_key(&path.file_name().unwrap_or_default().to_string_lossy()));
+
+    for chip in chips {
+        // Two sockets both have a "coretemp Core 0", the directory keeps them apart
+        let dir = chip
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        // Older drivers keep their attributes in the device directory
+        let chip = if chip.join("name").exists() {
+            chip
+        } else {
+            chip.join("device")
+        };
+        let Some(name) = read_trimmed(&chip.join("name")) else {
+            continue;
+        };
+        for index in attribute_indices(&chip, "temp") {
+            let prefix = format!("temp{}", index);
+            let Some(current) = read_millidegrees(&chip, &prefix, "input") else {
+                continue;
+            };
+            sensors.temperatures.push(Temperature {
+                label: sensor_label(&chip, &name, &prefix),
+                chip: dir.clone(),
+                current_celsius: current,
+                max_celsius: read_millidegrees(&chip, &prefix, "max"),
+                critical_celsius: read_millidegrees(&chip, &prefix, "crit"),
+            });
+        }
+        for index in attribute_indices(&chip, "fan") {
+            let prefix = format!("fan{}", index);
+            let Some(rpm) = read_trimmed(&chip.join(format!("{}_input", prefix)))
+                .and_then(|value| value.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            sensors.fans.push(Fan {
+                label: sensor_label(&chip, &name, &prefix),
+                chip: dir.clone(),
+                rpm,
+            });
+        }
+    }
+    Ok(sensors)
+}
+
+// Numbers N of every "<kind>N_input" attribute, in ascending order
+fn attribute_indices(chip: &Path, kind: &str) -> Vec<u32> {
+    let mut indices: Vec<u32> = fs::read_dir(chip)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    entry
+                        .file_name()
+                        .to_str()?
+                        .strip_prefix(kind)?
+                        .strip_suffix("_input")?
+                        .parse()
+                        .ok()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    indices.sort_unstable();
+    indices
+}
+
+// "coretemp Package id 0", or "acpitz temp1" when the driver has no label
+fn sensor_label(chip: &Path, name: &str, prefix: &str) -> String {
+    let label =
+        read_trimmed(&chip.join(format!("{}_label", prefix))).unwrap_or_else(|| prefix.to_string());
+    format!("{} {}", name, label)
+}
+
+// Unset trip points read as 0 on some chips, treat them as missing
+fn read_millidegrees(chip: &Path, prefix: &str, attribute: &str) -> Option<f32> {
+    let value: i64 = read_trimmed(&chip.join(format!("{}_{}", prefix, attribute)))?
+        .parse()
+        .ok()?;
+    (value != 0 || attribute == "input").then_some(value as f32 / MILLIDEGREES)
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    let value = fs::read_to_string(path).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+// Sorts "hwmon10" after "hwmon2"
+fn natural_key(name: &str) -> (String, u32) {
+    let digits = name.trim_start_matches(|c: char| !c.is_ascii_digit());
+    let prefix = &name[..name.len() - digits.len()];
+    (prefix.to_string(), digits.parse().unwrap_or_default())
+}
@@ -1,41 +1,47 @@
+use crate::error::{MachineInfoError, Result};
+use crate::sys::provider::{self, DiskReading};
 use crate::types::Storage;
 use sysinfo::Disks;
 
 impl Storage {
     pub fn get_storage_connection() -> Disks {
-        let running_disks = Disks::new_with_refreshed_list();
-        running_disks
+        Disks::new_with_refreshed_list()
     }
-    pub fn get_storage_info(passed_disks: &mut Disks) -> Self {
-        // Declare Constants
-        const BYTES_TO_GB: f64 = 1_000_000_000.0;
-
+    pub fn get_storage_info(passed_disks: &mut Disks) -> Result<Self> {
+        // Start Unwrapping if we find a disk
+        provider::read_disks(passed_disks)?
+            .first()
+            .map(Self::from_reading)
+            .ok_or_else(|| MachineInfoError::NoData("no disks mounted".to_string()))
+    }
+    pub fn get_all_storage_info(passed_disks: &mut Disks) -> Result<Vec<Self>> {
+        // Pack one struct per mounted disk
+        Ok(provider::read_disks(passed_disks)?
+            .iter()
+            .map(Self::from_reading)
+            .collect())
+    }
+    pub fn from_reading(disk: &DiskReading) -> Self {
         // Declare Variables
         let mut my_storage = Self::default();
+        let mut percent_used = 0.0;
 
-        // Refresh disk info
-        passed_disks.refresh(true);
+        let used_space = disk.total_bytes.saturating_sub(disk.available_bytes);
+        if disk.total_bytes > 0 && used_space > 0 {
+            percent_used = (used_space as f64 / disk.total_bytes as f64 * 100.0) as f32;
+        }
 
-        // Start Unwrapping if we find a disk
-        if let Some(disk) = passed_disks.first() {
-            let mut percent_used = 0.0;
-            let unwrapped_disk_name = disk.name().to_str();
-            let unwrapped_disk_size = disk.total_space() as f64 / BYTES_TO_GB;
-            let unwrapped_disk_space = disk.available_space() as f64 / BYTES_TO_GB;
-            let used_space = unwrapped_disk_size - unwrapped_disk_space;
-            if unwrapped_disk_size > 0.0 && used_space > 0.0 {
-                percent_used = (used_space / unwrapped_disk_size) * 100.0;
-            }
+        my_storage.name = Some(disk.name.clone());
+        my_storage.mount_point = Some(disk.mount_point.clone());
+        my_storage.file_system = Some(disk.file_system.clone());
+        my_storage.type_ = Some(disk.kind.clone());
+        my_storage.is_removable = disk.is_removable;
+        my_storage.total_bytes = disk.total_bytes;
+        my_storage.free_bytes = disk.available_bytes;
+        my_storage.used_bytes = used_space;
+        my_storage.percent_used = percent_used;
 
-            my_storage.name = Some(String::from(unwrapped_disk_name.unwrap_or_default()));
-            my_storage.total_space = format!("{:.2} GB", unwrapped_disk_size).into();
-            my_storage.free_space = format!("{:.2} GB", unwrapped_disk_space).into();
-            my_storage.used_space = format!("{:.2} GB", used_space).into();
-            my_storage.percent_used = format!("{:.2} %", percent_used).into()
-        } else {
-            eprintln!("Error: Data not found, returning default values");
-        }
-        // Return a packed struct (or default)
+        // Return a packed struct
         my_storage
     }
 }
@@ -0,0 +1,164 @@
+use crate::error::{MachineInfoError, Result};
+use crate::types::{LoggedInUser, MachineIdentity, SystemInfo};
+use sysinfo::System;
+
+// Declare Constants
+// Linux utmp records (glibc, also used by musl): see utmp(5)
+const UTMP_RECORD_SIZE: usize = 384;
+const UTMP_USER_PROCESS: i16 = 7;
+const UTMP_LINE: (usize, usize) = (8, 32);
+const UTMP_USER: (usize, usize) = (44, 32);
+const UTMP_HOST: (usize, usize) = (76, 256);
+const UTMP_TV_SEC: usize = 340;
+
+impl SystemInfo {
+    // Static host information plus uptime and the current login sessions
+    pub fn read() -> Result<Self> {
+        let kernel_version = System::kernel_version();
+        if kernel_version.is_none() && System::name().is_none() {
+            return Err(MachineInfoError::NoData("operating system".to_string()));
+        }
+        Ok(SystemInfo {
+            hostname: System::host_name(),
+            os_name: System::name(),
+            os_version: System::long_os_version(),
+            kernel_version,
+            distribution_id: Some(System::distribution_id()).filter(|id| !id.is_empty()),
+            architecture: host_architecture(),
+            emulated_architecture: translated_host().map(|_| process_architecture().to_string()),
+            uptime_secs: System::uptime(),
+            boot_time: System::boot_time(),
+            // Sessions are a nice to have, the rest is still worth showing without them
+            users: read_logged_in_users().unwrap_or_default(),
+        })
+    }
+    pub fn identity(&self) -> MachineIdentity {
+        MachineIdentity {
+            hostname: self.hostname.clone(),
+            os_name: self.os_name.clone(),
+            os_version: self.os_version.clone(),
+            kernel_version: self.kernel_version.clone(),
+            architecture: self.architecture.clone(),
+        }
+    }
+}
+
+impl MachineIdentity {
+    // Reads the static host information, none of it needs a refresh
+    pub fn read() -> Self {
+        MachineIdentity {
+            hostname: System::host_name(),
+            os_name: System::name(),
+            os_version: System::long_os_version(),
+            kernel_version: System::kernel_version(),
+            architecture: host_architecture(),
+        }
+    }
+}
+
+// Architecture of the machine itself. uname reports what this process runs as,
+// which under Rosetta or x64 emulation on Windows ARM64 is the emulated one.
+// qemu-user fakes uname and /proc/cpuinfo alike, so Linux has nothing better.
+pub fn host_architecture() -> Option<String> {
+    translated_host().or_else(|| Some(System::cpu_arch()).filter(|arch| !arch.is_empty()))
+}
+
+// What this binary was built for, in the names the OS uses for the host
+fn process_architecture() -> &'static str {
+    match std::env::consts::ARCH {
+        "aarch64" if cfg!(any(target_os = "macos", windows)) => "arm64",
+        arch => arch,
+    }
+}
+
+// Rosetta only translates x86_64 code on Apple silicon. Intel Macs don't know
+// the sysctl at all, which reads as not translated.
+#[cfg(target_os = "macos")]
+fn translated_host() -> Option<String> {
+    let mut translated: libc::c_int = 0;
+    let mut size = std::mem::size_of::<libc::c_int>();
+    // SAFETY: the buffer is a c_int and `size` says so
+    let result = unsafe {
+        libc::sysctlbyname(
+            c"sysctl.proc_translated".as_ptr(),
+            (&raw mut translated).cast(),
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    (result == 0 && translated == 1).then(|| "arm64".to_string())
+}
+
+#[cfg(windows)]
+fn translated_host() -> Option<String> {
+    use windows_sys::Win32::System::SystemInformation::{
+        IMAGE_FILE_MACHINE, IMAGE_FILE_MACHINE_AMD64, IMAGE_FILE_MACHINE_ARM64,
+        IMAGE_FILE_MACHINE_I386,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, IsWow64Process2};
+
+    let mut process: IMAGE_FILE_MACHINE = 0;
+    let mut native: IMAGE_FILE_MACHINE = 0;
+    // SAFETY: both out-pointers are valid, the pseudo handle needs no closing
+    if unsafe { IsWow64Process2(GetCurrentProcess(), &mut process, &mut native) } == 0 {
+        return None;
+    }
+    let host = match native {
+        IMAGE_FILE_MACHINE_AMD64 => "x86_64",
+        IMAGE_FILE_MACHINE_ARM64 => "arm64",
+        IMAGE_FILE_MACHINE_I386 => "x86",
+        _ => return None,
+    };
+    // x64 code on ARM64 runs without WOW64 and leaves `process` unknown, so
+    // compare against the build target instead
+    (host != process_architecture()).then(|| host.to_string())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn translated_host() -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+pub fn read_logged_in_users() -> Result<Vec<LoggedInUser>> {
+    // Older systems only have the /var/run path, which is a link to /run elsewhere
+    let bytes = std::fs::read("/run/utmp").or_else(|_| std::fs::read("/var/run/utmp"))?;
+    Ok(parse_utmp(&bytes))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_logged_in_users() -> Result<Vec<LoggedInUser>> {
+    Err(MachineInfoError::Unsupported("Logged-in users".to_string()))
+}
+
+// Keeps the user sessions from the raw utmp file, other records (boot time,
+// run level, dead processes) are skipped. A truncated last record is ignored.
+pub fn parse_utmp(bytes: &[u8]) -> Vec<LoggedInUser> {
+    bytes
+        .chunks_exact(UTMP_RECORD_SIZE)
+        .filter(|record| i16::from_ne_bytes([record[0], record[1]]) == UTMP_USER_PROCESS)
+        .map(|record| {
+            let login_time = i32::from_ne_bytes(
+                record[UTMP_TV_SEC..UTMP_TV_SEC + 4]
+                    .try_into()
+                    .unwrap_or_default(),
+            );
+            let host = utmp_string(record, UTMP_HOST);
+            LoggedInUser {
+                name: utmp_string(record, UTMP_USER),
+                terminal: utmp_string(record, UTMP_LINE),
+                host: (!host.is_empty()).then_some(host),
+                login_time: login_time.max(0) as u64,
+            }
+        })
+        .filter(|user| !user.name.is_empty())
+        .collect()
+}
+
+// Fixed-size field, NUL padded but not necessarily NUL terminated
+fn utmp_string(record: &[u8], (offset, length): (usize, usize)) -> String {
+    let field = &record[offset..offset + length];
+    let end = field.iter().position(|b| *b == 0).unwrap_or(length);
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
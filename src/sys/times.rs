@@ -0,0 +1,104 @@
+use crate::error::Result;
+use crate::types::{CpuTimes, Processor};
+
+// user, nice, system, idle, iowait, irq, softirq and steal, in that order.
+// guest and guest_nice follow in /proc/stat but are already part of user and nice.
+const STATES: usize = 8;
+
+// Cumulative time per CPU state in clock ticks; the percentages are the
+// difference between two readings
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CpuTimeCounters {
+    pub total: [u64; STATES],               // The aggregate "cpu" line
+    pub per_cpu: Vec<(u32, [u64; STATES])>, // cpuN lines, online CPUs only
+}
+
+impl CpuTimeCounters {
+    #[cfg(target_os = "linux")]
+    pub fn read() -> Result<Self> {
+        let stat = std::fs::read_to_string(crate::sys::load::STAT_PATH)?;
+        Self::parse(&stat).ok_or_else(|| {
+            crate::error::MachineInfoError::NoData("CPU times in /proc/stat".to_string())
+        })
+    }
+    #[cfg(not(target_os = "linux"))]
+    pub fn read() -> Result<Self> {
+        Err(crate::error::MachineInfoError::Unsupported(
+            "CPU time breakdown".to_string(),
+        ))
+    }
+    // The "cpu" and "cpuN" lines of /proc/stat. Kernels before 2.6.11 have no
+    // steal column, missing columns read as 0.
+    pub fn parse(stat: &str) -> Option<Self> {
+        let mut total = None;
+        let mut per_cpu = Vec::new();
+        for line in stat.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(name) = fields.next().and_then(|name| name.strip_prefix("cpu")) else {
+                continue;
+            };
+            let mut ticks = [0; STATES];
+            for (tick, value) in ticks.iter_mut().zip(fields) {
+                *tick = value.parse().ok()?;
+            }
+            if name.is_empty() {
+                total = Some(ticks);
+            } else if let Ok(id) = name.parse() {
+                per_cpu.push((id, ticks));
+            }
+        }
+        Some(CpuTimeCounters {
+            total: total?,
+            per_cpu,
+        })
+    }
+}
+
+impl CpuTimes {
+    // Percentages between two readings of the same CPU, None when no tick passed
+    pub fn between(previous: &[u64; STATES], current: &[u64; STATES]) -> Option<Self> {
+        let mut delta = [0; STATES];
+        for (state, delta) in delta.iter_mut().enumerate() {
+            *delta = current[state].saturating_sub(previous[state]);
+        }
+        let elapsed: u64 = delta.iter().sum();
+        if elapsed == 0 {
+            return None;
+        }
+        let percent = |state: usize| (delta[state] as f64 * 100.0 / elapsed as f64) as f32;
+        Some(CpuTimes {
+            user_percent: percent(0),
+            nice_percent: percent(1),
+            system_percent: percent(2),
+            idle_percent: percent(3),
+            iowait_percent: percent(4),
+            irq_percent: percent(5),
+            softirq_percent: percent(6),
+            steal_percent: percent(7),
+        })
+    }
+}
+
+impl Processor {
+    // Fills the aggregate and per-core breakdown from the counters read since the
+    // last call. Cores are matched by position, the same order sysinfo lists them
+    // in. The first call only stores a baseline.
+    pub fn update_times(
+        &mut self,
+        last_counters: &mut Option<CpuTimeCounters>,
+        current: Option<CpuTimeCounters>,
+    ) {
+        if let (Some(previous), Some(current)) = (last_counters.as_ref(), current.as_ref()) {
+            self.times = CpuTimes::between(&previous.total, &current.total);
+            for (core, (id, ticks)) in self.per_core.iter_mut().zip(&current.per_cpu) {
+                // By id, in case a CPU went offline in between
+                core.times = previous
+                    .per_cpu
+                    .iter()
+                    .find(|(previous_id, _)| previous_id == id)
+                    .and_then(|(_, previous)| CpuTimes::between(previous, ticks));
+            }
+        }
+        *last_counters = current;
+    }
+}
@@ -0,0 +1,204 @@
+use crate::error::{MachineInfoError, Result};
+use crate::types::{CpuCache, CpuTopology, NumaNode};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Declare Constants
+pub const SYSFS_SYSTEM_ROOT: &str = "/sys/devices/system";
+pub const CPUINFO_PATH: &str = "/proc/cpuinfo";
+
+impl CpuTopology {
+    #[cfg(target_os = "linux")]
+    pub fn read() -> Result<Self> {
+        let cpuinfo = fs::read_to_string(CPUINFO_PATH).ok();
+        read_topology(Path::new(SYSFS_SYSTEM_ROOT), cpuinfo.as_deref())
+    }
+    #[cfg(not(target_os = "linux"))]
+    pub fn read() -> Result<Self> {
+        Err(MachineInfoError::Unsupported("CPU topology".to_string()))
+    }
+}
+
+// Reads the topology below `root` (normally /sys/devices/system): sockets and
+// cores from cpu/cpuN/topology, caches from cpu/cpuN/cache and NUMA membership
+// from node/nodeN. Containers and some VMs hide the topology directories, then
+// /proc/cpuinfo fills in sockets and cores.
+pub fn read_topology(root: &Path, cpuinfo: Option<&str>) -> Result<CpuTopology> {
+    let cpus = online_cpus(&root.join("cpu"));
+    let mut packages = BTreeSet::new();
+    let mut cores = BTreeSet::new();
+    for (_, dir) in &cpus {
+        let package = read_trimmed(&dir.join("topology/physical_package_id"));
+        let core = read_trimmed(&dir.join("topology/core_id"));
+        if let (Some(package), Some(core)) = (package, core) {
+            packages.insert(package.clone());
+            cores.insert((package, core));
+        }
+    }
+    let mut logical_cpus = cpus.len();
+    if let Some(text) = cpuinfo
+        && (cores.is_empty() || logical_cpus == 0)
+    {
+        let (count, cpuinfo_packages, cpuinfo_cores) = cpuinfo_topology(text);
+        logical_cpus = logical_cpus.max(count);
+        if cores.is_empty() {
+            packages = cpuinfo_packages;
+            cores = cpuinfo_cores;
+        }
+    }
+    if logical_cpus == 0 {
+        return Err(MachineInfoError::NoData(
+            "no CPU topology found".to_string(),
+        ));
+    }
+
+    // Without core ids every logical CPU counts as its own core
+    let physical_cores = if cores.is_empty() {
+        logical_cpus
+    } else {
+        cores.len()
+    };
+    Ok(CpuTopology {
+        sockets: packages.len().max(1),
+        physical_cores,
+        logical_cpus,
+        threads_per_core: logical_cpus.div_ceil(physical_cores).max(1),
+        caches: read_caches(&cpus),
+        numa_nodes: read_numa_nodes(&root.join("node")),
+    })
+}
+
+// "0-3,8,10-11" as written in sysfs cpu lists
+pub fn parse_cpu_list(list: &str) -> Vec<u32> {
+    let mut cpus = Vec::new();
+    for part in list.trim().split(',').filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                if let (Ok(start), Ok(end)) = (start.parse::<u32>(), end.parse::<u32>()) {
+                    cpus.extend(start..=end);
+                }
+            }
+            None => cpus.extend(part.parse::<u32>().ok()),
+        }
+    }
+    cpus
+}
+
+// cpuN directories by number, leaving out CPUs that were taken offline
+fn online_cpus(cpu_root: &Path) -> Vec<(u32, PathBuf)> {
+    let mut cpus: Vec<(u32, PathBuf)> = numbered_entries(cpu_root, "cpu")
+        .into_iter()
+        .filter(|(_, dir)| read_trimmed(&dir.join("online")).is_none_or(|online| online != "0"))
+        .collect();
+    cpus.sort_by_key(|(index, _)| *index);
+    cpus
+}
+
+// Each cache instance is listed once per CPU that shares it, so instances are
+// told apart by their shared_cpu_list
+fn read_caches(cpus: &[(u32, PathBuf)]) -> Vec<CpuCache> {
+    let mut caches: BTreeMap<(u32, String), (u64, BTreeSet<String>)> = BTreeMap::new();
+    for (_, dir) in cpus {
+        for (_, index) in numbered_entries(&dir.join("cache"), "index") {
+            let level = read_trimmed(&index.join("level")).and_then(|level| level.parse().ok());
+            let kind = read_trimmed(&index.join("type"));
+            let size = read_trimmed(&index.join("size")).and_then(|size| parse_cache_size(&size));
+            let (Some(level), Some(kind), Some(size)) = (level, kind, size) else {
+                continue;
+            };
+            let shared = read_trimmed(&index.join("shared_cpu_list")).unwrap_or_default();
+            let entry = caches.entry((level, kind)).or_default();
+            entry.0 = size;
+            entry.1.insert(shared);
+        }
+    }
+    // BTreeMap order is L1 Data, L1 Instruction, L2 Unified, ...
+    caches
+        .into_iter()
+        .map(|((level, kind), (size_bytes, shared))| CpuCache {
+            level,
+            kind,
+            size_bytes,
+            instances: shared.len(),
+        })
+        .collect()
+}
+
+fn read_numa_nodes(node_root: &Path) -> Vec<NumaNode> {
+    let mut nodes: Vec<NumaNode> = numbered_entries(node_root, "node")
+        .into_iter()
+        .map(|(id, dir)| NumaNode {
+            id,
+            cpus: read_trimmed(&dir.join("cpulist"))
+                .map(|list| parse_cpu_list(&list))
+                .unwrap_or_default(),
+        })
+        .collect();
+    nodes.sort_by_key(|node| node.id);
+    nodes
+}
+
+// Logical CPU count, packages and (package, core) pairs from /proc/cpuinfo
+fn cpuinfo_topology(text: &str) -> (usize, BTreeSet<String>, BTreeSet<(String, String)>) {
+    let mut count = 0;
+    let mut packages = BTreeSet::new();
+    let mut cores = BTreeSet::new();
+    for block in text.split("\n\n") {
+        let field = |name: &str| cpuinfo_field(block, name);
+        if field("processor").is_none() {
+            continue;
+        }
+        count += 1;
+        if let (Some(package), Some(core)) = (field("physical id"), field("core id")) {
+            packages.insert(package.clone());
+            cores.insert((package, core));
+        }
+    }
+    (count, packages, cores)
+}
+
+// Value of `name` in one processor block of /proc/cpuinfo ("core id\t\t: 3")
+pub(crate) fn cpuinfo_field(block: &str, name: &str) -> Option<String> {
+    block.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == name).then(|| value.trim().to_string())
+    })
+}
+
+// "32K", "1280K", "32M" or plain bytes
+fn parse_cache_size(size: &str) -> Option<u64> {
+    let (number, multiplier) = match size.as_bytes().last()? {
+        b'K' => (&size[..size.len() - 1], 1024),
+        b'M' => (&size[..size.len() - 1], 1024 * 1024),
+        b'G' => (&size[..size.len() - 1], 1024 * 1024 * 1024),
+        _ => (size, 1),
+    };
+    Some(number.trim().parse::<u64>().ok()? * multiplier)
+}
+
+// Entries named "<prefix>N" below `dir`
+fn numbered_entries(dir: &Path, prefix: &str) -> Vec<(u32, PathBuf)> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let index = entry
+                        .file_name()
+                        .to_str()?
+                        .strip_prefix(prefix)?
+                        .parse()
+                        .ok()?;
+                    Some((index, entry.path()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    let value = fs::read_to_string(path).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
@@ -1,4 +1,5 @@
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
 pub struct WindowInformation {
     pub x: i32, // X Start Position
     pub y: i32, // Y Start Position
@@ -8,39 +9,437 @@ pub struct WindowInformation {
     pub fullscreen: bool // Is the window fullscreen?
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
 pub struct Dimension {
     // String because app.db uses TEXT for the column type
     pub x_position: Option<i32>,
     pub y_position: Option<i32>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
 pub struct Storage {
     pub name: Option<String>,
-    pub usage: Option<String>,
     pub mount_point: Option<String>,
     pub file_system: Option<String>,
-    pub type_: Option<String>,
-    pub total_space: Option<String>,  // e.g. "500.00 GB"
-    pub free_space: Option<String>,   // e.g. "120.20 GB"
-    pub used_space: Option<String>,   // e.g. "379.80 GB"
-    pub percent_used: Option<String>, // e.g. "75.96 %"
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub type_: Option<String>, // e.g. "SSD", "HDD"
+    pub is_removable: bool,
+    pub total_bytes: u64,   // e.g. 500_000_000_000
+    pub free_bytes: u64,    // e.g. 120_200_000_000
+    pub used_bytes: u64,    // e.g. 379_800_000_000
+    pub percent_used: f32,  // e.g. 75.96
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
 pub struct Processor {
     pub name: Option<String>,
     pub vendor: Option<String>,
-    pub family: Option<String>,
-    pub speed: Option<String>,
-    pub cores: Option<String>,
-    pub usage: Option<String>,
+    pub architecture: Option<String>, // Architecture the CPU runs as, e.g. "x86_64"
+    pub frequency_mhz: u64, // Frequency of the first core
+    pub cores: usize,       // Number of logical CPUs
+    pub usage_percent: f32, // Average usage across all cores
+    pub per_core: Vec<ProcessorCore>,
+    pub topology: Option<CpuTopology>, // None where the platform doesn't expose it
+    pub features: Option<CpuFeatures>, // None where neither /proc/cpuinfo nor CPUID is available
+    pub load: Option<CpuLoad>, // None where the platform has no load average
+    pub times: Option<CpuTimes>, // All CPUs together, None where /proc/stat is missing
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct ProcessorCore {
+    pub index: usize,       // Position as reported by sysinfo, starting at 0
+    pub usage_percent: f32,
+    pub frequency_mhz: u64,
+    pub times: Option<CpuTimes>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
 pub struct Memory {
-    pub total: Option<String>,
-    pub used: Option<String>,
-    pub free: Option<String>,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub free_bytes: u64, // Available memory, including reclaimable cache
+    pub swap_total_bytes: u64,
+    pub swap_used_bytes: u64,
+    pub swap_free_bytes: u64,
+    pub swap_in_pages_per_sec: f64, // From /proc/vmstat, 0 where unsupported
+    pub swap_out_pages_per_sec: f64,
+    // /proc/meminfo breakdown, all 0 where the platform doesn't expose it
+    pub unused_bytes: u64, // MemFree, memory nothing is using at all
+    pub buffers_bytes: u64,
+    pub cached_bytes: u64, // Page cache, includes shared memory
+    pub shared_bytes: u64,
+    pub slab_reclaimable_bytes: u64,
+    pub slab_unreclaimable_bytes: u64,
+    pub dirty_bytes: u64,
+    pub writeback_bytes: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct MemorySegment {
+    pub label: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Network {
+    pub name: Option<String>,
+    pub mac_address: Option<String>,
+    pub ipv4_addresses: Vec<String>, // e.g. "192.168.1.20/24"
+    pub ipv6_addresses: Vec<String>,
+    pub mtu: u64,
+    pub link_state: Option<String>, // e.g. "up", "down", None when the platform doesn't say
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub rx_packets: u64, // Packets since the previous refresh
+    pub tx_packets: u64,
+    pub rx_errors: u64, // Errors since the previous refresh
+    pub tx_errors: u64,
+    pub total_rx_bytes: u64,
+    pub total_tx_bytes: u64,
+    pub total_rx_packets: u64,
+    pub total_tx_packets: u64,
+    pub total_rx_errors: u64,
+    pub total_tx_errors: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: Option<String>,
+    pub user: Option<String>,
+    pub cpu_usage_percent: f32, // Can exceed 100 % on multi-core machines
+    pub rss_bytes: u64,
+    pub virtual_bytes: u64,
+    pub start_time: u64, // Unix timestamp in seconds
+    pub command_line: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProcessSortKey {
+    #[default]
+    Pid,
+    Name,
+    User,
+    Cpu,
+    Rss,
+    Virtual,
+    StartTime,
+    Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessSignal {
+    Terminate, // SIGTERM, lets the process clean up
+    Kill,      // SIGKILL, cannot be caught
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct HistorySettings {
+    pub sample_interval_secs: u32,  // Minimum time between two recorded samples
+    pub raw_retention_secs: u32,    // Raw samples older than this become minute aggregates
+    pub minute_retention_secs: u32, // Minute aggregates older than this become hour aggregates
+    pub hour_retention_secs: u32,   // Hour aggregates older than this are deleted
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct SamplerSettings {
+    pub refresh_interval_ms: u32, // Time between two snapshots of the displayed pages
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct HistoryRecorder {
+    pub settings: HistorySettings,
+    // Runtime state of this process, not part of the settings
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub last_sample: Option<i64>, // Unix timestamp of the last recorded sample
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub last_compaction: Option<i64>, // Unix timestamp of the last downsampling pass
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct CpuSample {
+    pub timestamp: i64,
+    pub resolution: u32, // 0 for raw samples, otherwise the bucket size in seconds
+    pub usage_avg: f32,
+    pub usage_max: f32,
+    pub frequency_mhz: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct MemorySample {
+    pub timestamp: i64,
+    pub resolution: u32,
+    pub used_avg_bytes: u64,
+    pub used_max_bytes: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct SensorSample {
+    pub timestamp: i64,
+    pub resolution: u32,
+    pub sensor: String, // Label of the temperature sensor
+    pub chip: String,   // hwmon directory of the sensor, empty where sysinfo reported it
+    pub temperature_avg: f32,
+    pub temperature_max: f32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct StorageSample {
+    pub timestamp: i64,
+    pub resolution: u32,
+    pub mount_point: String,
+    pub used_avg_bytes: u64,
+    pub used_max_bytes: u64,
+    pub total_bytes: u64,
+}
+
+// Which parts of the machine a refresh should read
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Subsystems {
+    pub cpu: bool,
+    pub memory: bool,
+    pub storage: bool,
+    pub network: bool,
+    pub processes: bool,
+    pub system: bool,
+    pub sensors: bool,
+}
+
+// Readings taken by one refresh, None for subsystems that were not read
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Snapshot {
+    pub timestamp: i64, // Unix timestamp of the refresh
+    // Sections that were not read are left out of the JSON rather than written as null
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub cpu: Option<Processor>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub memory: Option<Memory>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub storage: Option<Vec<Storage>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub network: Option<Vec<Network>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub processes: Option<Vec<ProcessInfo>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub system: Option<SystemInfo>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub sensors: Option<Sensors>,
+}
+
+// Which machine a snapshot was taken on
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct MachineIdentity {
+    pub hostname: Option<String>,
+    pub os_name: Option<String>,    // e.g. "Ubuntu"
+    pub os_version: Option<String>, // e.g. "Linux 24.04 Ubuntu"
+    pub kernel_version: Option<String>,
+    pub architecture: Option<String>, // e.g. "x86_64"
+}
+
+// A snapshot together with the machine it was taken on, what exports contain
+// Files without a snapshot are not exports, everything else may be missing
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MachineProfile {
+    #[cfg_attr(feature = "serde", serde(rename = "machine", default))]
+    pub identity: MachineIdentity,
+    pub snapshot: Snapshot,
+}
+
+// One property that differs between two profiles, None where a side doesn't have it
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Difference {
+    pub section: String, // e.g. "cpu", "storage"
+    pub item: String,    // e.g. a mount point, empty for single-item sections
+    pub field: String,   // e.g. "Cores"
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+// What an alert rule watches
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AlertMetric {
+    #[default]
+    CpuUsage,        // Percent, average of all cores
+    MemoryUsage,     // Percent of total memory
+    MemoryAvailable, // Bytes
+    SwapUsage,       // Percent of total swap
+    DiskUsage,       // Percent, checked for every mounted disk
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AlertComparison {
+    #[default]
+    Above,
+    Below,
+}
+
+// e.g. CPU usage above 90 % for 30 s
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct AlertRule {
+    pub id: i64, // 0 until the rule is saved
+    pub name: String,
+    pub metric: AlertMetric,
+    pub comparison: AlertComparison,
+    pub threshold: f64,      // In the unit of the metric
+    pub duration_secs: u32,  // How long the condition has to hold before the rule fires
+    pub enabled: bool,
+}
+
+// A rule that fired
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct AlertEvent {
+    pub timestamp: i64, // Unix timestamp of the snapshot that fired the rule
+    pub rule_id: i64,
+    pub rule_name: String,
+    pub item: String, // Mount point for disk rules, empty otherwise
+    pub value: f64,
+    pub message: String,
+}
+
+// A login session as recorded in utmp
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct LoggedInUser {
+    pub name: String,
+    pub terminal: String,     // e.g. "tty1", "pts/0"
+    pub host: Option<String>, // Remote host for SSH sessions, display for X sessions
+    pub login_time: u64,      // Unix timestamp in seconds
+}
+
+// Operating system and host, the block every support ticket starts with
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct SystemInfo {
+    pub hostname: Option<String>,
+    pub os_name: Option<String>,               // e.g. "Ubuntu"
+    pub os_version: Option<String>,            // e.g. "Linux 24.04 Ubuntu"
+    pub kernel_version: Option<String>,        // e.g. "6.8.0-45-generic"
+    pub distribution_id: Option<String>,       // e.g. "ubuntu", from os-release on Linux
+    pub architecture: Option<String>,          // The host's, e.g. "arm64" even under Rosetta
+    pub emulated_architecture: Option<String>, // What this process runs as when translated
+    pub uptime_secs: u64,
+    pub boot_time: u64,                        // Unix timestamp in seconds
+    pub users: Vec<LoggedInUser>,
+}
+
+// One temperature sensor, labelled "<chip> <sensor>", e.g. "coretemp Package id 0"
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Temperature {
+    pub label: String,
+    pub chip: String, // hwmon directory, e.g. "hwmon2", tells apart chips with the same name
+    pub current_celsius: f32,
+    pub max_celsius: Option<f32>,      // Where the hardware starts throttling, if reported
+    pub critical_celsius: Option<f32>, // Where the hardware shuts down, if reported
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Fan {
+    pub label: String,
+    pub chip: String, // hwmon directory, like Temperature::chip
+    pub rpm: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Sensors {
+    pub temperatures: Vec<Temperature>,
+    pub fans: Vec<Fan>, // Only where hwmon exposes them
+}
+
+// How the logical CPUs map onto sockets, cores, caches and memory nodes
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct CpuTopology {
+    pub sockets: usize,
+    pub physical_cores: usize, // Across all sockets
+    pub logical_cpus: usize,   // Online CPUs only
+    pub threads_per_core: usize,
+    pub caches: Vec<CpuCache>, // One entry per level and type, L1 first
+    pub numa_nodes: Vec<NumaNode>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct CpuCache {
+    pub level: u32,
+    pub kind: String,    // "Data", "Instruction" or "Unified"
+    pub size_bytes: u64, // Size of one instance
+    pub instances: usize,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct NumaNode {
+    pub id: u32,
+    pub cpus: Vec<u32>,
+}
+
+// Share of the time since the previous reading each CPU state took, in percent.
+// Guest time is part of user and nice, the way the kernel counts it.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct CpuTimes {
+    pub user_percent: f32,
+    pub nice_percent: f32,
+    pub system_percent: f32,
+    pub idle_percent: f32,
+    pub iowait_percent: f32, // Idle with I/O outstanding
+    pub irq_percent: f32,
+    pub softirq_percent: f32,
+    pub steal_percent: f32, // Taken by the hypervisor for other guests
+}
+
+// Run-queue load. The averages count runnable (and on Linux uninterruptible)
+// tasks, so they compare against the number of logical CPUs.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct CpuLoad {
+    pub load_average_1m: f64,
+    pub load_average_5m: f64,
+    pub load_average_15m: f64,
+    // From /proc/stat, all 0 where the platform doesn't expose it
+    pub running_processes: u64,
+    pub blocked_processes: u64, // Waiting for I/O
+    pub context_switches_per_sec: f64,
+    pub interrupts_per_sec: f64,
+}
+
+// Identification and instruction-set extensions of the first CPU. On ARM the family is
+// the architecture version, the model the part number and the stepping the revision.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct CpuFeatures {
+    pub family: Option<u32>,
+    pub model: Option<u32>,
+    pub stepping: Option<u32>,
+    pub microcode: Option<String>, // e.g. "0x430", as the kernel prints it
+    pub flags: Vec<String>,        // Kernel names, sorted, e.g. "avx2", "sse4_2", "asimd"
 }
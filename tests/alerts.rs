@@ -0,0 +1,235 @@
+use machine_info::alerts::AlertEvaluator;
+use machine_info::cli::{self, AlertsCommand, CliCommand, CliOptions};
+use machine_info::{
+    AlertComparison, AlertEvent, AlertMetric, AlertRule, MachineInfoError, Memory, Processor,
+    Snapshot, Storage,
+};
+use rusqlite::Connection;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+fn cpu_rule() -> AlertRule {
+    AlertRule {
+        id: 1,
+        name: "High CPU usage".to_string(),
+        metric: AlertMetric::CpuUsage,
+        comparison: AlertComparison::Above,
+        threshold: 90.0,
+        duration_secs: 30,
+        enabled: true,
+    }
+}
+
+fn cpu_snapshot(timestamp: i64, usage_percent: f32) -> Snapshot {
+    Snapshot {
+        timestamp,
+        cpu: Some(Processor {
+            usage_percent,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn disk(mount_point: &str, percent_used: f32) -> Storage {
+    Storage {
+        mount_point: Some(mount_point.to_string()),
+        percent_used,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn rule_fires_once_the_condition_held_long_enough() {
+    let mut evaluator = AlertEvaluator::new(vec![cpu_rule()]);
+
+    assert!(evaluator.evaluate(&cpu_snapshot(100, 95.0)).is_empty());
+    assert!(evaluator.evaluate(&cpu_snapshot(120, 99.0)).is_empty());
+    let events = evaluator.evaluate(&cpu_snapshot(130, 97.5));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].rule_id, 1);
+    assert_eq!(events[0].timestamp, 130);
+    assert_eq!(events[0].value, 97.5);
+    assert_eq!(
+        events[0].message,
+        "High CPU usage: CPU usage is 97.50 % (above 90.00 % for 30 s)"
+    );
+
+    // Still breached: no repeat until the value recovers
+    assert!(evaluator.evaluate(&cpu_snapshot(200, 99.0)).is_empty());
+    // Snapshots without CPU readings don't reset the rule
+    assert!(evaluator.evaluate(&Snapshot::default()).is_empty());
+    assert!(evaluator.evaluate(&cpu_snapshot(210, 10.0)).is_empty());
+    assert!(evaluator.evaluate(&cpu_snapshot(220, 95.0)).is_empty());
+    assert_eq!(evaluator.evaluate(&cpu_snapshot(250, 95.0)).len(), 1);
+}
+
+#[test]
+fn short_spikes_do_not_fire() {
+    let mut evaluator = AlertEvaluator::new(vec![cpu_rule()]);
+    assert!(evaluator.evaluate(&cpu_snapshot(0, 95.0)).is_empty());
+    assert!(evaluator.evaluate(&cpu_snapshot(20, 50.0)).is_empty());
+    assert!(evaluator.evaluate(&cpu_snapshot(40, 95.0)).is_empty());
+    assert!(evaluator.evaluate(&cpu_snapshot(60, 95.0)).is_empty());
+
+    // Disabled rules are never checked
+    let mut evaluator = AlertEvaluator::new(vec![AlertRule {
+        enabled: false,
+        duration_secs: 0,
+        ..cpu_rule()
+    }]);
+    assert!(evaluator.evaluate(&cpu_snapshot(0, 100.0)).is_empty());
+}
+
+#[test]
+fn default_rules_cover_memory_and_every_disk() {
+    let rules: Vec<AlertRule> = AlertRule::defaults()
+        .into_iter()
+        .enumerate()
+        .map(|(index, rule)| AlertRule {
+            id: index as i64 + 1,
+            ..rule
+        })
+        .collect();
+    let mut evaluator = AlertEvaluator::new(rules);
+    let snapshot = Snapshot {
+        timestamp: 1,
+        memory: Some(Memory {
+            total_bytes: 16 * GIB,
+            free_bytes: GIB / 2,
+            ..Default::default()
+        }),
+        storage: Some(vec![
+            disk("/", 96.0),
+            disk("/home", 50.0),
+            disk("/data", 99.5),
+        ]),
+        ..Default::default()
+    };
+
+    let events = evaluator.evaluate(&snapshot);
+    let fired: Vec<(&str, &str)> = events
+        .iter()
+        .map(|event| (event.rule_name.as_str(), event.item.as_str()))
+        .collect();
+    assert_eq!(
+        fired,
+        [
+            ("Low memory", ""),
+            ("Disk almost full", "/"),
+            ("Disk almost full", "/data"),
+        ]
+    );
+    assert_eq!(
+        events[0].message,
+        "Low memory: Available memory is 0.50 GB (below 1.00 GB)"
+    );
+    assert_eq!(
+        events[2].message,
+        "Disk almost full: Disk usage of /data is 99.50 % (above 95.00 %)"
+    );
+}
+
+#[test]
+fn rules_and_events_are_stored_in_the_database() {
+    let conn = Connection::open_in_memory().unwrap();
+    AlertRule::create_table(&conn).unwrap();
+    AlertEvent::create_table(&conn).unwrap();
+
+    // A new database starts with the default rules
+    let rules = AlertRule::load_all(&conn).unwrap();
+    assert_eq!(rules.len(), AlertRule::defaults().len());
+    assert_eq!(rules[0].metric, AlertMetric::CpuUsage);
+
+    // Deleted rules are not seeded again
+    AlertRule::delete_from_db(&conn, rules[0].id).unwrap();
+    AlertRule::create_table(&conn).unwrap();
+    assert_eq!(AlertRule::load_all(&conn).unwrap().len(), rules.len() - 1);
+
+    let id = AlertRule {
+        id: 0,
+        name: "Swap".to_string(),
+        metric: AlertMetric::SwapUsage,
+        comparison: AlertComparison::Above,
+        threshold: 50.0,
+        duration_secs: 10,
+        enabled: true,
+    }
+    .save_to_db(&conn)
+    .unwrap();
+    let mut saved = AlertRule::load_all(&conn).unwrap().pop().unwrap();
+    assert_eq!(saved.id, id);
+    saved.enabled = false;
+    saved.save_to_db(&conn).unwrap();
+    assert!(!AlertRule::load_all(&conn).unwrap().pop().unwrap().enabled);
+
+    let mut evaluator = AlertEvaluator::new(vec![cpu_rule()]);
+    evaluator.evaluate(&cpu_snapshot(0, 95.0));
+    for event in evaluator.evaluate(&cpu_snapshot(30, 95.0)) {
+        event.save_to_db(&conn).unwrap();
+    }
+    let history = AlertEvent::load_recent(&conn, 10).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].rule_name, "High CPU usage");
+    assert_eq!(history[0].timestamp, 30);
+}
+
+#[test]
+fn rules_edited_from_the_cli_persist_and_are_evaluated() {
+    let conn = Connection::open_in_memory().unwrap();
+    AlertRule::create_table(&conn).unwrap();
+    let run = |args: &[&str]| {
+        let options = CliOptions::parse(args).unwrap().unwrap();
+        let Some(CliCommand::Alerts(command)) = options.command else {
+            panic!("not an alerts command: {:?}", args);
+        };
+        cli::run_alerts(&conn, &command).unwrap()
+    };
+
+    // Rule 1 is the default CPU rule, lower it and drop its duration
+    assert_eq!(
+        run(&["alerts", "set", "1", "50", "0"]),
+        "  1  on   High CPU usage: CPU usage above 50.00 %\n"
+    );
+    let rules = AlertRule::load_all(&conn).unwrap();
+    assert_eq!((rules[0].threshold, rules[0].duration_secs), (50.0, 0));
+    let events = AlertEvaluator::new(rules).evaluate(&cpu_snapshot(100, 60.0));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].rule_id, 1);
+
+    // Disabled rules stay stored but no longer fire
+    run(&["alerts", "disable", "1"]);
+    let rules = AlertRule::load_all(&conn).unwrap();
+    assert!(!rules[0].enabled);
+    assert!(
+        AlertEvaluator::new(rules)
+            .evaluate(&cpu_snapshot(100, 60.0))
+            .is_empty()
+    );
+
+    run(&["alerts", "add", "Swap", "swap_usage", "above", "80", "60"]);
+    run(&["alerts", "delete", "3"]);
+    let list = run(&["alerts"]);
+    assert!(list.starts_with("  1  off  High CPU usage: CPU usage above 50.00 %\n"));
+    assert!(list.ends_with("  4  on   Swap: Swap usage above 80.00 % for 60 s\n"));
+    assert!(!list.contains("Disk almost full"));
+}
+
+#[test]
+fn bad_alerts_commands_are_rejected() {
+    for args in [
+        &["alerts", "add", "Fans", "fan_speed", "above", "10"][..],
+        &["alerts", "add", "CPU", "cpu_usage", "over", "10"],
+        &["alerts", "set", "one", "10"],
+        &["alerts", "set", "1", "ten"],
+        &["alerts", "rename", "1"],
+    ] {
+        assert!(CliOptions::parse(args).is_err(), "{:?}", args);
+    }
+    let conn = Connection::open_in_memory().unwrap();
+    AlertRule::create_table(&conn).unwrap();
+    assert!(matches!(
+        cli::run_alerts(&conn, &AlertsCommand::Delete(99)),
+        Err(MachineInfoError::NoData(_))
+    ));
+}
@@ -0,0 +1,42 @@
+use machine_info::chart::{ChartBuffer, ChartRange};
+use machine_info::sampler::MIN_INTERVAL;
+use std::time::{Duration, Instant};
+
+#[test]
+fn longest_range_is_kept_at_the_fastest_interval() {
+    let start = Instant::now();
+    let mut buffer = ChartBuffer::for_longest_range();
+    let hour = ChartRange::Hour.duration();
+    let samples = (hour.as_millis() / MIN_INTERVAL.as_millis()) as u32;
+    for i in 0..=samples {
+        buffer.push_at(start + MIN_INTERVAL * i, 50.0);
+    }
+    // A full hour of samples, the oldest one exactly an hour old
+    assert_eq!(buffer.len(), samples as usize + 1);
+    let now = start + MIN_INTERVAL * samples;
+    assert!(
+        buffer
+            .to_path_commands(ChartRange::Hour, now)
+            .starts_with("M 0.00 50.00 ")
+    );
+
+    // Anything older than that is dropped
+    buffer.push_at(now + MIN_INTERVAL, 50.0);
+    assert_eq!(buffer.len(), samples as usize + 1);
+}
+
+#[test]
+fn slow_intervals_keep_few_samples() {
+    let start = Instant::now();
+    let mut buffer = ChartBuffer::new(Duration::from_secs(60));
+    for i in 0..10 {
+        buffer.push_at(start + Duration::from_secs(30) * i, i as f32 * 10.0);
+    }
+    // Only 3:30, 4:00 and the newest sample at 4:30 fit into a minute
+    assert_eq!(buffer.len(), 3);
+    assert_eq!(buffer.latest(), Some(90.0));
+    assert_eq!(
+        buffer.to_path_commands(ChartRange::Minute, start + Duration::from_secs(270)),
+        "M 0.00 30.00 L 50.00 20.00 L 100.00 10.00"
+    );
+}
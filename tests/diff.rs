@@ -0,0 +1,170 @@
+#[cfg(feature = "serde")]
+use machine_info::MachineInfoError;
+use machine_info::cli::{CliCommand, CliOptions};
+use machine_info::diff;
+#[cfg(feature = "serde")]
+use machine_info::export::{parse_profile, render_json_export};
+use machine_info::sys::collector::Collector;
+use machine_info::sys::fake::FakeProvider;
+use machine_info::sys::provider::{CpuReading, DiskReading, MemoryReading};
+use machine_info::{MachineIdentity, MachineProfile, Subsystems};
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+fn sample_profile(hostname: &str, cores: usize, memory_gib: u64) -> MachineProfile {
+    let provider = FakeProvider::new()
+        .with_cpus(vec![
+            CpuReading {
+                brand: "Test CPU".to_string(),
+                vendor_id: "GenuineIntel".to_string(),
+                usage_percent: 10.0,
+                frequency_mhz: 3000,
+            };
+            cores
+        ])
+        .with_memory(MemoryReading {
+            total_bytes: memory_gib * GIB,
+            available_bytes: GIB,
+            ..Default::default()
+        })
+        .with_disks(vec![DiskReading {
+            name: "/dev/nvme0n1p2".to_string(),
+            mount_point: "/".to_string(),
+            file_system: "ext4".to_string(),
+            kind: "SSD".to_string(),
+            total_bytes: 500_000_000_000,
+            available_bytes: 100_000_000_000,
+            ..Default::default()
+        }]);
+    let (snapshot, _) = Collector::with_provider(provider).refresh(Subsystems {
+        cpu: true,
+        memory: true,
+        storage: true,
+        ..Default::default()
+    });
+    MachineProfile {
+        identity: MachineIdentity {
+            hostname: Some(hostname.to_string()),
+            os_version: Some("Linux 24.04 Ubuntu".to_string()),
+            kernel_version: Some("6.8.0".to_string()),
+            architecture: Some("x86_64".to_string()),
+            ..Default::default()
+        },
+        snapshot,
+    }
+}
+
+#[test]
+fn identical_machines_have_no_differences() {
+    let mine = sample_profile("mine", 8, 16);
+    let mut later = sample_profile("mine", 8, 16);
+    // Live readings are not part of the comparison
+    later.snapshot.cpu.as_mut().unwrap().usage_percent = 95.0;
+    later.snapshot.storage.as_mut().unwrap()[0].free_bytes = 0;
+
+    let differences = diff::diff_profiles(&mine, &later);
+    assert!(differences.is_empty());
+    assert_eq!(
+        diff::render_text("a", "b", &differences),
+        "No differences\n"
+    );
+}
+
+#[test]
+fn hardware_and_os_changes_are_reported() {
+    let mine = sample_profile("mine", 8, 16);
+    let mut theirs = sample_profile("theirs", 16, 32);
+    theirs.identity.kernel_version = Some("6.11.0".to_string());
+    theirs.snapshot.storage.as_mut().unwrap()[0].file_system = Some("btrfs".to_string());
+
+    let differences = diff::diff_profiles(&mine, &theirs);
+    let fields: Vec<(&str, &str)> = differences
+        .iter()
+        .map(|d| (d.section.as_str(), d.field.as_str()))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("machine", "Hostname"),
+            ("machine", "Kernel"),
+            ("cpu", "Cores"),
+            ("memory", "Total Memory"),
+            ("storage", "File System"),
+        ]
+    );
+    assert_eq!(differences[2].left.as_deref(), Some("8"));
+    assert_eq!(differences[2].right.as_deref(), Some("16"));
+    assert_eq!(differences[3].right.as_deref(), Some("32.00 GB"));
+    assert_eq!(differences[4].item, "/");
+
+    let text = diff::render_text("mine.json", "theirs.json", &differences);
+    assert!(text.starts_with("Property               mine.json  theirs.json\n"));
+    assert!(text.contains("cpu Cores              8          16\n"));
+}
+
+#[test]
+fn missing_disks_are_reported_once() {
+    let mine = sample_profile("mine", 8, 16);
+    let mut theirs = sample_profile("mine", 8, 16);
+    theirs.snapshot.storage = Some(Vec::new());
+
+    let differences = diff::diff_profiles(&mine, &theirs);
+    assert_eq!(differences.len(), 1);
+    assert_eq!(differences[0].field, "Mounted");
+    assert_eq!(differences[0].left.as_deref(), Some("Mounted"));
+    assert_eq!(differences[0].right, None);
+    #[cfg(feature = "serde")]
+    {
+        let json: serde_json::Value =
+            serde_json::from_str(&diff::render_json(&differences)).unwrap();
+        assert_eq!(json[0]["right"], serde_json::Value::Null);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn exported_profiles_can_be_read_back() {
+    let mut profile = sample_profile("build \"01\"\n", 2, 8);
+    profile.snapshot.timestamp = 1_700_000_000;
+    let json = render_json_export(&profile.identity, &profile.snapshot);
+
+    let read = parse_profile(&json).unwrap();
+    assert_eq!(read.identity, profile.identity);
+    assert_eq!(read.snapshot.timestamp, 1_700_000_000);
+    assert!(read.snapshot.network.is_none());
+    assert!(diff::diff_profiles(&profile, &read).is_empty());
+    let cpu = read.snapshot.cpu.unwrap();
+    assert_eq!(cpu.per_core.len(), 2);
+    assert_eq!(cpu.per_core[1].frequency_mhz, 3000);
+
+    assert!(matches!(
+        parse_profile("{\"cpu\": {}}"),
+        Err(MachineInfoError::Parse(_))
+    ));
+    assert!(matches!(
+        parse_profile("{\"snapshot\": "),
+        Err(MachineInfoError::Parse(_))
+    ));
+}
+
+#[test]
+fn diff_command_takes_one_or_two_files() {
+    let options = CliOptions::parse(["diff", "--json", "a.json", "b.json"])
+        .unwrap()
+        .unwrap();
+    assert!(options.json);
+    assert_eq!(
+        options.command,
+        Some(CliCommand::Diff(vec![
+            "a.json".to_string(),
+            "b.json".to_string()
+        ]))
+    );
+
+    let options = CliOptions::parse(["snapshot"]).unwrap().unwrap();
+    assert_eq!(options.command, Some(CliCommand::Snapshot));
+
+    assert!(CliOptions::parse(["diff"]).is_err());
+    assert!(CliOptions::parse(["diff", "a", "b", "c"]).is_err());
+    assert!(CliOptions::parse(["snapshot", "a.json"]).is_err());
+}
@@ -0,0 +1,121 @@
+use machine_info::MachineIdentity;
+use machine_info::export::{self, ExportFormat};
+use machine_info::sys::collector::Collector;
+use machine_info::sys::fake::FakeProvider;
+use machine_info::sys::provider::{CpuReading, DiskReading, MemoryReading};
+use machine_info::{Snapshot, Subsystems};
+use std::fs;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+fn sample_identity() -> MachineIdentity {
+    MachineIdentity {
+        hostname: Some("build-01".to_string()),
+        os_name: Some("Ubuntu".to_string()),
+        os_version: Some("Linux 24.04 Ubuntu".to_string()),
+        kernel_version: Some("6.8.0".to_string()),
+        architecture: Some("x86_64".to_string()),
+    }
+}
+
+fn sample_snapshot() -> Snapshot {
+    let provider = FakeProvider::new()
+        .with_cpus(vec![CpuReading {
+            brand: "Test CPU".to_string(),
+            vendor_id: "GenuineIntel".to_string(),
+            usage_percent: 40.0,
+            frequency_mhz: 3200,
+        }])
+        .with_memory(MemoryReading {
+            total_bytes: 8 * GIB,
+            available_bytes: 4 * GIB,
+            ..Default::default()
+        })
+        .with_disks(vec![DiskReading {
+            name: "Disk, \"Main\"".to_string(),
+            mount_point: "/".to_string(),
+            file_system: "ext4".to_string(),
+            kind: "SSD".to_string(),
+            total_bytes: 500_000_000_000,
+            available_bytes: 125_000_000_000,
+            ..Default::default()
+        }]);
+    let (mut snapshot, _) = Collector::with_provider(provider).refresh(Subsystems::all());
+    snapshot.timestamp = 1_735_787_045; // 2025-01-02 03:04:05 UTC
+    snapshot
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_export_contains_identity_and_readings() {
+    let json = ExportFormat::Json
+        .render(&sample_identity(), &sample_snapshot())
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["machine"]["hostname"], "build-01");
+    assert_eq!(json["machine"]["architecture"], "x86_64");
+    let snapshot = &json["snapshot"];
+    assert_eq!(snapshot["timestamp"], 1_735_787_045);
+    assert_eq!(snapshot["cpu"]["usage_percent"], 40.0);
+    assert_eq!(snapshot["memory"]["total_bytes"], 8_589_934_592u64);
+    assert_eq!(snapshot["storage"][0]["mount_point"], "/");
+    assert_eq!(snapshot["storage"][0]["type"], "SSD");
+    // Profiles only carry what a bug report needs
+    assert!(snapshot.get("network").is_none());
+}
+
+#[cfg(not(feature = "serde"))]
+#[test]
+fn json_export_needs_serde() {
+    let json = ExportFormat::Json.render(&sample_identity(), &sample_snapshot());
+    assert!(matches!(
+        json,
+        Err(machine_info::MachineInfoError::Unsupported(_))
+    ));
+}
+
+#[test]
+fn csv_export_has_one_quoted_reading_per_row() {
+    let csv = ExportFormat::Csv
+        .render(&sample_identity(), &sample_snapshot())
+        .unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("section,item,field,value"));
+    assert!(csv.contains("\nmachine,,hostname,build-01\n"));
+    assert!(csv.contains("\ncpu,core0,frequency_mhz,3200\n"));
+    assert!(csv.contains("\nmemory,,used_bytes,4294967296\n"));
+    assert!(csv.contains("\nstorage,/,name,\"Disk, \"\"Main\"\"\"\n"));
+    assert!(csv.lines().all(|line| !line.is_empty()));
+}
+
+#[test]
+fn markdown_export_uses_tables() {
+    let markdown = ExportFormat::Markdown
+        .render(&sample_identity(), &sample_snapshot())
+        .unwrap();
+    assert!(markdown.starts_with("# Machine Profile\n\nTaken 2025-01-02 03:04:05 UTC\n"));
+    assert!(markdown.contains("| Hostname | build-01 |\n"));
+    assert!(markdown.contains("| CPU-ID | Test CPU |\n"));
+    assert!(markdown.contains("| Total Memory | 8.00 GB |\n"));
+    assert!(markdown.contains("| / | Disk, \"Main\" | ext4 | SSD | 500.00 GB |"));
+}
+
+#[test]
+fn export_is_written_with_a_descriptive_name() {
+    let identity = sample_identity();
+    let snapshot = sample_snapshot();
+    assert_eq!(
+        export::file_name(&identity, snapshot.timestamp, ExportFormat::Markdown),
+        "MachineInfo-build-01-20250102-030405.md"
+    );
+
+    let dir = std::env::temp_dir().join(format!("machine_info_export_{}", std::process::id()));
+    let path = export::write_export(&dir, ExportFormat::Csv, &identity, &snapshot).unwrap();
+    assert_eq!(path.extension().unwrap(), "csv");
+    let written = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        written,
+        ExportFormat::Csv.render(&identity, &snapshot).unwrap()
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,125 @@
+use machine_info::cli::CliOptions;
+use machine_info::exporter::{self, CONTENT_TYPE, render_metrics};
+use machine_info::sys::collector::Collector;
+use machine_info::sys::fake::FakeProvider;
+use machine_info::sys::provider::{CpuReading, DiskReading, MemoryReading, NetworkReading};
+use machine_info::{Snapshot, Subsystems};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+fn sample_collector() -> Collector<FakeProvider> {
+    Collector::with_provider(
+        FakeProvider::new()
+            .with_cpus(vec![CpuReading {
+                brand: "Test \"Fast\" CPU".to_string(),
+                vendor_id: "GenuineIntel".to_string(),
+                usage_percent: 25.0,
+                frequency_mhz: 3000,
+            }])
+            .with_memory(MemoryReading {
+                total_bytes: 1024,
+                available_bytes: 256,
+                ..Default::default()
+            })
+            .with_disks(vec![DiskReading {
+                name: "/dev/sda1".to_string(),
+                mount_point: "/".to_string(),
+                file_system: "ext4".to_string(),
+                total_bytes: 1000,
+                available_bytes: 400,
+                ..Default::default()
+            }])
+            .with_networks(vec![NetworkReading {
+                name: "eth0".to_string(),
+                link_state: Some("up".to_string()),
+                mtu: 1500,
+                total_received_bytes: 4096,
+                ..Default::default()
+            }]),
+    )
+}
+
+fn sample_snapshot() -> Snapshot {
+    sample_collector().refresh(Subsystems::all()).0
+}
+
+#[test]
+fn metrics_use_the_openmetrics_text_format() {
+    let text = render_metrics(&sample_snapshot(), Subsystems::all());
+
+    assert!(text.ends_with("# EOF\n"));
+    assert!(text.contains("# TYPE machineinfo_cpu_usage_ratio gauge\n"));
+    assert!(text.contains("# UNIT machineinfo_cpu_usage_ratio ratio\n"));
+    assert!(text.contains("machineinfo_cpu_usage_ratio 0.25\n"));
+    assert!(text.contains("machineinfo_cpu_core_frequency_hertz{core=\"0\"} 3000000000\n"));
+    assert!(text.contains("machineinfo_memory_used_bytes 768\n"));
+    assert!(text.contains(
+        "machineinfo_storage_used_bytes{device=\"/dev/sda1\",mountpoint=\"/\",fstype=\"ext4\"} 600\n"
+    ));
+    assert!(text.contains("# TYPE machineinfo_network_receive_bytes counter\n"));
+    assert!(text.contains("machineinfo_network_receive_bytes_total{interface=\"eth0\"} 4096\n"));
+    assert!(text.contains("machineinfo_network_up{interface=\"eth0\"} 1\n"));
+    // Quotes in label values are escaped
+    assert!(text.contains("name=\"Test \\\"Fast\\\" CPU\""));
+}
+
+#[test]
+fn metrics_report_failed_subsystems() {
+    let snapshot = Snapshot {
+        cpu: sample_snapshot().cpu,
+        ..Default::default()
+    };
+    let requested = Subsystems {
+        cpu: true,
+        memory: true,
+        ..Default::default()
+    };
+    let text = render_metrics(&snapshot, requested);
+
+    assert!(text.contains("machineinfo_collector_success{subsystem=\"cpu\"} 1\n"));
+    assert!(text.contains("machineinfo_collector_success{subsystem=\"memory\"} 0\n"));
+    assert!(!text.contains("subsystem=\"storage\""));
+    assert!(!text.contains("machineinfo_memory_total_bytes"));
+}
+
+#[test]
+fn serve_option_takes_an_optional_address() {
+    let options = CliOptions::parse(["--serve"]).unwrap().unwrap();
+    assert_eq!(options.serve.as_deref(), Some(exporter::DEFAULT_ADDRESS));
+
+    let options = CliOptions::parse(["--serve=0.0.0.0:9000", "--cpu"])
+        .unwrap()
+        .unwrap();
+    assert_eq!(options.serve.as_deref(), Some("0.0.0.0:9000"));
+    assert!(options.cpu && !options.memory);
+}
+
+fn get(address: &str, path: &str) -> String {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn exporter_serves_metrics_over_http() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || {
+        exporter::serve(listener, &mut sample_collector(), Subsystems::all()).unwrap();
+    });
+
+    let response = get(&address, "/metrics");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains(&format!("Content-Type: {}\r\n", CONTENT_TYPE)));
+    assert!(response.contains("machineinfo_memory_total_bytes 1024\n"));
+    assert!(response.ends_with("# EOF\n"));
+
+    let response = get(&address, "/metrics?name[]=machineinfo_up");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+
+    let response = get(&address, "/nothing");
+    assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+}
@@ -0,0 +1,179 @@
+use machine_info::cli::{self, CliOptions};
+use machine_info::diff::diff_profiles;
+use machine_info::display::FeaturesDisplay;
+#[cfg(feature = "serde")]
+use machine_info::export::{parse_profile, render_json_export};
+use machine_info::exporter::render_metrics;
+use machine_info::sys::collector::Collector;
+use machine_info::sys::fake::FakeProvider;
+use machine_info::sys::features::{decode_cpuid_signature, parse_cpuinfo};
+use machine_info::sys::provider::CpuReading;
+use machine_info::{CpuFeatures, MachineIdentity, MachineProfile, Subsystems};
+use std::process::ExitCode;
+
+// First block of /proc/cpuinfo on a Sapphire Rapids server, flags shortened
+const X86_CPUINFO: &str = "processor\t: 0\n\
+                           vendor_id\t: GenuineIntel\n\
+                           cpu family\t: 6\n\
+                           model\t\t: 143\n\
+                           model name\t: Intel(R) Xeon(R) Platinum 8480+\n\
+                           stepping\t: 8\n\
+                           microcode\t: 0x2b000590\n\
+                           flags\t\t: fpu lm sse sse2 pni ssse3 cx16 sse4_1 sse4_2 popcnt \
+                           lahf_lm avx avx2 bmi1 bmi2 f16c fma abm movbe xsave aes\n\n\
+                           processor\t: 1\n\
+                           cpu family\t: 6\n\n";
+
+// Raspberry Pi 4, the part number is hex
+const ARM_CPUINFO: &str = "processor\t: 0\n\
+                           BogoMIPS\t: 108.00\n\
+                           Features\t: fp asimd evtstrm crc32 cpuid\n\
+                           CPU implementer\t: 0x41\n\
+                           CPU architecture: 8\n\
+                           CPU variant\t: 0x0\n\
+                           CPU part\t: 0xd08\n\
+                           CPU revision\t: 3\n\n\
+                           Hardware\t: BCM2835\n";
+
+fn x86_features() -> CpuFeatures {
+    parse_cpuinfo(X86_CPUINFO).unwrap()
+}
+
+#[test]
+fn cpuinfo_is_parsed_on_x86_and_arm() {
+    let features = x86_features();
+    assert_eq!(features.family, Some(6));
+    assert_eq!(features.model, Some(143));
+    assert_eq!(features.stepping, Some(8));
+    assert_eq!(features.microcode.as_deref(), Some("0x2b000590"));
+    assert_eq!(features.flags.first().map(String::as_str), Some("abm"));
+    assert_eq!(features.flags.len(), 21);
+
+    let features = parse_cpuinfo(ARM_CPUINFO).unwrap();
+    assert_eq!(
+        features,
+        CpuFeatures {
+            family: Some(8),
+            model: Some(0xd08),
+            stepping: Some(3),
+            microcode: None,
+            flags: ["asimd", "cpuid", "crc32", "evtstrm", "fp"]
+                .map(str::to_string)
+                .to_vec(),
+        }
+    );
+    assert_eq!(features.x86_64_level(), None);
+
+    assert!(parse_cpuinfo("").is_err());
+}
+
+#[test]
+fn flags_are_searched_and_graded() {
+    let features = x86_features();
+    assert!(features.has_flag("AVX2"));
+    assert!(!features.has_flag("avx512f"));
+    assert_eq!(features.search_flags("Sse4"), ["sse4_1", "sse4_2"]);
+    assert_eq!(features.search_flags("").len(), features.flags.len());
+    assert!(features.search_flags("avx512").is_empty());
+
+    // Everything up to v3, AVX-512 is missing for v4
+    assert_eq!(features.x86_64_level(), Some(3));
+    let mut without_popcnt = features.clone();
+    without_popcnt.flags.retain(|flag| flag != "popcnt");
+    assert_eq!(without_popcnt.x86_64_level(), Some(1));
+
+    let display = FeaturesDisplay::from(&features);
+    assert_eq!(display.identification, "family 6, model 143, stepping 8");
+    assert_eq!(display.level, "x86-64-v3");
+
+    // CPUID leaf 1 of the same part, and of a Zen 4 with its extended family
+    assert_eq!(decode_cpuid_signature(0x000806f8), (6, 143, 8));
+    assert_eq!(decode_cpuid_signature(0x00a60f12), (25, 97, 2));
+}
+
+#[test]
+fn features_are_reported_and_survive_an_export() {
+    let provider = FakeProvider::new()
+        .with_cpus(vec![CpuReading::default()])
+        .with_features(x86_features());
+    let mut collector = Collector::with_provider(provider);
+    let (snapshot, errors) = collector.refresh(Subsystems {
+        cpu: true,
+        ..Default::default()
+    });
+    assert!(errors.is_empty());
+
+    let text = cli::render_text(&snapshot);
+    assert!(text.contains("(family 6, model 143, stepping 8)\n"));
+    assert!(text.contains("  Microcode:      0x2b000590\n  ISA Level:      x86-64-v3\n"));
+    assert!(text.contains("  Flags:          abm aes avx avx2 bmi1"));
+
+    let metrics = render_metrics(
+        &snapshot,
+        Subsystems {
+            cpu: true,
+            ..Default::default()
+        },
+    );
+    assert!(metrics.contains(
+        "machineinfo_cpu_model_info{cpu_family=\"6\",model=\"143\",stepping=\"8\",\
+         microcode=\"0x2b000590\",isa_level=\"x86-64-v3\"} 1\n"
+    ));
+    assert!(metrics.contains("machineinfo_cpu_flag_info{flag=\"avx2\"} 1\n"));
+
+    #[cfg(feature = "serde")]
+    {
+        let json = render_json_export(&MachineIdentity::default(), &snapshot);
+        let profile = parse_profile(&json).unwrap();
+        assert_eq!(profile.snapshot.cpu.unwrap().features, Some(x86_features()));
+    }
+}
+
+#[test]
+fn flags_are_searched_from_the_cli_and_compared_in_diffs() {
+    let options = CliOptions::parse(["--cpu-flags=avx"]).unwrap().unwrap();
+    assert_eq!(options.cpu_flags.as_deref(), Some("avx"));
+    let options = CliOptions::parse(["--cpu-flags"]).unwrap().unwrap();
+    assert_eq!(options.cpu_flags.as_deref(), Some(""));
+    // No match is a failing exit status for scripts, not an error or an exit
+    if cfg!(any(target_arch = "x86_64", target_arch = "aarch64")) {
+        let options = CliOptions::parse(["--cpu-flags=no_such_flag"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(cli::run(&options).unwrap(), ExitCode::FAILURE);
+    }
+
+    let profile = |features: CpuFeatures| {
+        let provider = FakeProvider::new()
+            .with_cpus(vec![CpuReading::default()])
+            .with_features(features);
+        let (snapshot, _) = Collector::with_provider(provider).refresh(Subsystems {
+            cpu: true,
+            ..Default::default()
+        });
+        MachineProfile {
+            identity: MachineIdentity::default(),
+            snapshot,
+        }
+    };
+    let mut older = x86_features();
+    older.flags.retain(|flag| !flag.starts_with("avx"));
+    older.microcode = Some("0x2b000400".to_string());
+    let differences = diff_profiles(&profile(older), &profile(x86_features()));
+    let fields: Vec<(&str, &str)> = differences
+        .iter()
+        .map(|difference| (difference.item.as_str(), difference.field.as_str()))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("", "Microcode"),
+            ("", "ISA Level"),
+            ("avx", "Flag"),
+            ("avx2", "Flag"),
+        ]
+    );
+    assert_eq!(differences[1].left.as_deref(), Some("x86-64-v2"));
+    assert_eq!(differences[2].left, None);
+    assert_eq!(differences[2].right.as_deref(), Some("present"));
+}
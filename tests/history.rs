@@ -0,0 +1,193 @@
+use machine_info::db::history::{RESOLUTION_HOUR, RESOLUTION_MINUTE, RESOLUTION_RAW};
+use machine_info::{HistoryRecorder, HistorySettings, Memory, Processor, Storage};
+use rusqlite::Connection;
+
+fn recorder(settings: HistorySettings) -> (Connection, HistoryRecorder) {
+    let conn = Connection::open_in_memory().unwrap();
+    HistoryRecorder::create_tables(&conn).unwrap();
+    (conn, HistoryRecorder::new(settings))
+}
+
+fn disk(mount_point: &str, used_bytes: u64) -> Storage {
+    Storage {
+        mount_point: Some(mount_point.to_string()),
+        used_bytes,
+        total_bytes: 1000,
+        ..Default::default()
+    }
+}
+
+// Records the same usage figure for the CPU, memory and every disk
+fn record(conn: &Connection, recorder: &HistoryRecorder, timestamp: i64, usage: u64) {
+    let cpu = Processor {
+        usage_percent: usage as f32,
+        frequency_mhz: 1000 + usage,
+        ..Default::default()
+    };
+    let memory = Memory {
+        used_bytes: usage,
+        total_bytes: 1000,
+        ..Default::default()
+    };
+    let storage = [disk("/", usage), disk("/home", usage * 2)];
+    recorder
+        .record_at(conn, timestamp, &cpu, &memory, &storage, &[])
+        .unwrap();
+}
+
+#[test]
+fn raw_samples_roll_up_into_minutes() {
+    let (conn, recorder) = recorder(HistorySettings {
+        raw_retention_secs: 60,
+        ..Default::default()
+    });
+    for (timestamp, usage) in [(0, 10), (20, 30), (40, 50), (70, 20), (200, 90)] {
+        record(&conn, &recorder, timestamp, usage);
+    }
+    // Raw rows before align(200 - 60) = 120 are folded into their minute
+    recorder.compact(&conn, 200).unwrap();
+
+    let cpu = HistoryRecorder::cpu_history(&conn, 0, 300).unwrap();
+    let rows: Vec<(i64, u32, f32, f32, u64)> = cpu
+        .iter()
+        .map(|s| {
+            (
+                s.timestamp,
+                s.resolution,
+                s.usage_avg,
+                s.usage_max,
+                s.frequency_mhz,
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (0, RESOLUTION_MINUTE, 30.0, 50.0, 1030),
+            (60, RESOLUTION_MINUTE, 20.0, 20.0, 1020),
+            (200, RESOLUTION_RAW, 90.0, 90.0, 1090),
+        ]
+    );
+
+    let memory = HistoryRecorder::memory_history(&conn, 0, 300).unwrap();
+    assert_eq!(memory.len(), 3);
+    assert_eq!(memory[0].used_avg_bytes, 30);
+    assert_eq!(memory[0].used_max_bytes, 50);
+    assert_eq!(memory[0].total_bytes, 1000);
+
+    // Each mount point keeps its own buckets
+    let storage = HistoryRecorder::storage_history(&conn, 0, 59).unwrap();
+    let rows: Vec<(&str, u64, u64)> = storage
+        .iter()
+        .map(|s| (s.mount_point.as_str(), s.used_avg_bytes, s.used_max_bytes))
+        .collect();
+    assert_eq!(rows, [("/", 30, 50), ("/home", 60, 100)]);
+}
+
+#[test]
+fn minutes_roll_up_into_hours_and_expire() {
+    let (conn, recorder) = recorder(HistorySettings {
+        raw_retention_secs: 60,
+        minute_retention_secs: 3600,
+        hour_retention_secs: 86_400,
+        ..Default::default()
+    });
+    for (timestamp, usage) in [(0, 10), (1800, 40), (3700, 70)] {
+        record(&conn, &recorder, timestamp, usage);
+    }
+    recorder.compact(&conn, 4000).unwrap();
+    let resolutions: Vec<u32> = HistoryRecorder::cpu_history(&conn, 0, 4000)
+        .unwrap()
+        .iter()
+        .map(|s| s.resolution)
+        .collect();
+    assert_eq!(resolutions, [RESOLUTION_MINUTE; 3]);
+
+    // Minutes before align(7500 - 3600) = 3600 become one hour row
+    recorder.compact(&conn, 7500).unwrap();
+    let cpu = HistoryRecorder::cpu_history(&conn, 0, 7500).unwrap();
+    assert_eq!(cpu.len(), 2);
+    assert_eq!((cpu[0].timestamp, cpu[0].resolution), (0, RESOLUTION_HOUR));
+    assert_eq!((cpu[0].usage_avg, cpu[0].usage_max), (25.0, 40.0));
+    assert_eq!(
+        (cpu[1].timestamp, cpu[1].resolution),
+        (3660, RESOLUTION_MINUTE)
+    );
+    let memory = HistoryRecorder::memory_history(&conn, 0, 7500).unwrap();
+    assert_eq!(
+        (memory[0].used_avg_bytes, memory[0].used_max_bytes),
+        (25, 40)
+    );
+
+    // A day later the first hour is gone, the second one only just rolled up
+    recorder.compact(&conn, 86_401).unwrap();
+    for table in ["CpuHistory", "MemoryHistory", "StorageHistory"] {
+        let rows: Vec<(i64, u32)> = conn
+            .prepare(&format!("SELECT timestamp, resolution FROM {table}"))
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let expected = if table == "StorageHistory" { 2 } else { 1 };
+        assert_eq!(rows, vec![(3600, RESOLUTION_HOUR); expected], "{table}");
+    }
+}
+
+#[test]
+fn samples_wait_for_the_interval() {
+    let mut recorder = HistoryRecorder::new(HistorySettings {
+        sample_interval_secs: 5,
+        ..Default::default()
+    });
+    assert!(recorder.is_due(1000));
+    recorder.last_sample = Some(1000);
+    assert!(!recorder.is_due(1004));
+    assert!(recorder.is_due(1005));
+
+    let conn = Connection::open_in_memory().unwrap();
+    HistoryRecorder::create_tables(&conn).unwrap();
+    assert!(
+        recorder
+            .record(&conn, &Processor::default(), &Memory::default(), &[], &[])
+            .unwrap()
+    );
+    // The second call lands inside the interval and writes nothing
+    assert!(
+        !recorder
+            .record(&conn, &Processor::default(), &Memory::default(), &[], &[])
+            .unwrap()
+    );
+    assert_eq!(
+        HistoryRecorder::cpu_history(&conn, 0, i64::MAX)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn settings_are_stored_in_the_database() {
+    let conn = Connection::open_in_memory().unwrap();
+    let recorder = HistoryRecorder::from_db(&conn).unwrap();
+    assert_eq!(recorder.settings.raw_retention_secs, 6 * 3600);
+
+    let settings = HistorySettings {
+        sample_interval_secs: 10,
+        raw_retention_secs: 600,
+        minute_retention_secs: 7200,
+        hour_retention_secs: 86_400,
+    };
+    settings.save_to_db(&conn).unwrap();
+    let recorder = HistoryRecorder::from_db(&conn).unwrap();
+    let stored = recorder.settings;
+    assert_eq!(
+        (
+            stored.sample_interval_secs,
+            stored.raw_retention_secs,
+            stored.minute_retention_secs,
+            stored.hour_retention_secs
+        ),
+        (10, 600, 7200, 86_400)
+    );
+}
@@ -0,0 +1,137 @@
+#[cfg(feature = "serde")]
+use machine_info::MachineIdentity;
+use machine_info::cli;
+use machine_info::display::LoadDisplay;
+#[cfg(feature = "serde")]
+use machine_info::export::{parse_profile, render_json_export};
+use machine_info::exporter::render_metrics;
+use machine_info::sys::collector::Collector;
+use machine_info::sys::fake::FakeProvider;
+use machine_info::sys::load::LoadCounters;
+use machine_info::sys::provider::CpuReading;
+use machine_info::{CpuLoad, Processor, Subsystems};
+use std::time::{Duration, Instant};
+
+const LOADAVG: &str = "2.50 1.75 0.40 3/812 40213\n";
+
+// /proc/stat with the per-CPU lines and most interrupt columns left out
+const STAT: &str = "cpu  10132153 290696 3084719 46828483 16683 0 25195 0 0 0\n\
+                    cpu0 1393280 32966 572056 13343292 6130 0 17875 0 0 0\n\
+                    intr 199292724 9 0 0 0 0 0\n\
+                    ctxt 1990473000\n\
+                    btime 1062191376\n\
+                    processes 2915\n\
+                    procs_running 3\n\
+                    procs_blocked 1\n";
+
+fn counters(context_switches: u64, interrupts: u64, at: Instant) -> LoadCounters {
+    LoadCounters {
+        load_average: [2.5, 1.75, 0.4],
+        running_processes: 3,
+        blocked_processes: 1,
+        context_switches,
+        interrupts,
+        at,
+    }
+}
+
+#[test]
+fn loadavg_and_stat_are_parsed() {
+    let at = Instant::now();
+    let parsed = LoadCounters::parse(LOADAVG, STAT, at).unwrap();
+    assert_eq!(parsed.load_average, [2.5, 1.75, 0.4]);
+    assert_eq!(parsed.running_processes, 3);
+    assert_eq!(parsed.blocked_processes, 1);
+    assert_eq!(parsed.context_switches, 1_990_473_000);
+    assert_eq!(parsed.interrupts, 199_292_724);
+
+    // Truncated files are no reading at all
+    assert!(LoadCounters::parse("2.50 1.75", STAT, at).is_none());
+    assert!(LoadCounters::parse(LOADAVG, "ctxt 1\n", at).is_none());
+}
+
+#[test]
+fn rates_come_from_two_readings() {
+    let start = Instant::now();
+    let mut last = None;
+    let mut cpu = Processor::default();
+
+    // The first reading is only a baseline
+    cpu.update_load(&mut last, Some(counters(1_000, 500, start)));
+    let load = cpu.load.clone().unwrap();
+    assert_eq!(load.load_average_1m, 2.5);
+    assert_eq!(load.running_processes, 3);
+    assert_eq!(load.context_switches_per_sec, 0.0);
+
+    let later = start + Duration::from_secs(2);
+    cpu.update_load(&mut last, Some(counters(11_000, 2_500, later)));
+    let load = cpu.load.clone().unwrap();
+    assert_eq!(load.context_switches_per_sec, 5_000.0);
+    assert_eq!(load.interrupts_per_sec, 1_000.0);
+
+    // A platform without load average clears it again
+    cpu.update_load(&mut last, None);
+    assert!(cpu.load.is_none());
+    assert!(last.is_none());
+}
+
+#[test]
+fn load_is_shown_relative_to_the_cpus() {
+    let load = CpuLoad {
+        load_average_1m: 6.0,
+        load_average_5m: 3.0,
+        load_average_15m: 1.0,
+        running_processes: 5,
+        blocked_processes: 0,
+        context_switches_per_sec: 5120.4,
+        interrupts_per_sec: 812.0,
+    };
+    assert_eq!(load.per_cpu(4), [1.5, 0.75, 0.25]);
+
+    let display = LoadDisplay::new(&load, 4);
+    assert_eq!(display.averages, "6.00, 3.00, 1.00");
+    assert_eq!(display.per_cpu, "1.50, 0.75, 0.25");
+    assert_eq!(display.run_queue, "5 running, 0 blocked");
+    assert_eq!(display.context_switches, "5120/s");
+    assert!(display.overloaded);
+    assert!(!LoadDisplay::new(&load, 8).overloaded);
+}
+
+#[test]
+fn load_is_part_of_the_cpu_report() {
+    let provider = FakeProvider::new()
+        .with_cpus(vec![CpuReading::default(); 2])
+        .with_load(counters(0, 0, Instant::now()));
+    let mut collector = Collector::with_provider(provider);
+    let requested = Subsystems {
+        cpu: true,
+        ..Default::default()
+    };
+    let (snapshot, errors) = collector.refresh(requested);
+    assert!(errors.is_empty());
+    let load = snapshot.cpu.as_ref().unwrap().load.clone().unwrap();
+    assert_eq!(load.load_average_5m, 1.75);
+
+    let text = cli::render_text(&snapshot);
+    assert!(text.contains("  Load Average:   2.50, 1.75, 0.40\n"));
+    assert!(text.contains("  Load per CPU:   1.25, 0.88, 0.20\n"));
+    assert!(text.contains("  Run Queue:      3 running, 1 blocked\n"));
+
+    let metrics = render_metrics(&snapshot, requested);
+    assert!(metrics.contains("machineinfo_cpu_load_average{window=\"15m\"} 0.4\n"));
+    assert!(metrics.contains("machineinfo_processes_blocked 1\n"));
+
+    #[cfg(feature = "serde")]
+    {
+        let json = render_json_export(&MachineIdentity::default(), &snapshot);
+        let profile = parse_profile(&json).unwrap();
+        assert_eq!(profile.snapshot.cpu.unwrap().load, Some(load));
+    }
+
+    // Providers without load average still report the CPU
+    let mut collector =
+        Collector::with_provider(FakeProvider::new().with_cpus(vec![CpuReading::default()]));
+    let (snapshot, errors) = collector.refresh(requested);
+    assert!(errors.is_empty());
+    assert_eq!(snapshot.cpu.unwrap().load, None);
+}
@@ -0,0 +1,316 @@
+use machine_info::cli::{self, CliOptions};
+use machine_info::display::{MemoryDisplay, NetworkDisplay, ProcessorDisplay, StorageDisplay};
+use machine_info::sys::collector::Collector;
+use machine_info::sys::fake::FakeProvider;
+use machine_info::sys::provider::{
+    CpuReading, DiskReading, MemoryReading, MetricsProvider, NetworkReading, SysinfoProvider,
+};
+use machine_info::{MachineInfoError, ProcessInfo, ProcessSignal, Subsystems, WindowInformation};
+use std::io;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+fn sample_provider() -> FakeProvider {
+    FakeProvider::new()
+        .with_cpus(vec![
+            CpuReading {
+                brand: "Test CPU".to_string(),
+                vendor_id: "GenuineIntel".to_string(),
+                usage_percent: 30.0,
+                frequency_mhz: 3200,
+            },
+            CpuReading {
+                brand: "Test CPU".to_string(),
+                vendor_id: "GenuineIntel".to_string(),
+                usage_percent: 10.0,
+                frequency_mhz: 2800,
+            },
+        ])
+        .with_memory(MemoryReading {
+            total_bytes: 8 * GIB,
+            available_bytes: 4 * GIB,
+            swap_total_bytes: 2 * GIB,
+            swap_used_bytes: GIB / 2,
+            swap_free_bytes: 3 * GIB / 2,
+            meminfo: Some(include_str!("fixtures/meminfo.txt").to_string()),
+        })
+        .with_disks(vec![DiskReading {
+            name: "/dev/sda1".to_string(),
+            mount_point: "/".to_string(),
+            file_system: "ext4".to_string(),
+            kind: "SSD".to_string(),
+            is_removable: false,
+            total_bytes: 500_000_000_000,
+            available_bytes: 125_000_000_000,
+        }])
+        .with_networks(vec![
+            NetworkReading {
+                name: "wlan0".to_string(),
+                elapsed_secs: 2.0,
+                received_bytes: 3_000_000,
+                transmitted_bytes: 1_000,
+                ..Default::default()
+            },
+            NetworkReading {
+                name: "eth0".to_string(),
+                mac_address: Some("00:11:22:33:44:55".to_string()),
+                ipv4_addresses: vec!["192.168.1.2/24".to_string()],
+                mtu: 1500,
+                link_state: Some("up".to_string()),
+                ..Default::default()
+            },
+        ])
+}
+
+#[test]
+fn cpu_usage_is_the_average_of_all_cores() {
+    let cpu = sample_provider().cpu_info().unwrap();
+    assert_eq!(cpu.cores, 2);
+    assert_eq!(cpu.usage_percent, 20.0);
+    assert_eq!(cpu.frequency_mhz, 3200);
+    assert_eq!(cpu.per_core[1].index, 1);
+    assert_eq!(cpu.per_core[1].frequency_mhz, 2800);
+
+    let display = ProcessorDisplay::from(&cpu);
+    assert_eq!(display.name, "Test CPU");
+    assert_eq!(display.speed, "3.20 GHz");
+    assert_eq!(display.usage, "20.00 %");
+}
+
+#[test]
+fn cpu_without_cores_is_no_data() {
+    let result = FakeProvider::new().cpu_info();
+    assert!(matches!(result, Err(MachineInfoError::NoData(_))));
+}
+
+#[test]
+fn memory_used_is_total_minus_available() {
+    let memory = sample_provider().memory_info().unwrap();
+    assert_eq!(memory.used_bytes, 4 * GIB);
+    assert_eq!(memory.free_bytes, 4 * GIB);
+
+    let display = MemoryDisplay::from(&memory);
+    assert_eq!(display.total, "8.00 GB");
+    assert_eq!(display.used, "4.00 GB");
+    assert_eq!(display.swap_used, "0.50 GB");
+}
+
+#[test]
+fn meminfo_fixture_fills_the_breakdown() {
+    let memory = sample_provider().memory_info().unwrap();
+    assert_eq!(memory.unused_bytes, GIB);
+    assert_eq!(memory.buffers_bytes, GIB / 4);
+    assert_eq!(memory.cached_bytes, 2 * GIB);
+    assert_eq!(memory.shared_bytes, GIB / 8);
+    assert_eq!(memory.slab_reclaimable_bytes, GIB / 2);
+    assert_eq!(memory.slab_unreclaimable_bytes, GIB / 4);
+    assert_eq!(memory.dirty_bytes, 4 * 1024 * 1024);
+    assert_eq!(memory.writeback_bytes, 0);
+
+    let segments = memory.get_segments();
+    let labels: Vec<&str> = segments.iter().map(|s| s.label.as_str()).collect();
+    assert_eq!(
+        labels,
+        ["Applications", "Kernel", "Buffers", "Cache", "Free"]
+    );
+    assert_eq!(segments[0].bytes, 4 * GIB);
+    assert_eq!(segments[3].bytes, 5 * GIB / 2);
+    assert_eq!(
+        segments.iter().map(|s| s.bytes).sum::<u64>(),
+        memory.total_bytes
+    );
+}
+
+#[test]
+fn memory_without_a_total_is_no_data() {
+    let result = FakeProvider::new().memory_info();
+    assert!(matches!(result, Err(MachineInfoError::NoData(_))));
+}
+
+#[test]
+fn memory_without_meminfo_falls_back_to_used_and_free() {
+    let mut provider = sample_provider();
+    provider.memory.meminfo = None;
+    let segments = provider.memory_info().unwrap().get_segments();
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].bytes, 4 * GIB);
+    assert_eq!(segments[1].bytes, 4 * GIB);
+}
+
+#[test]
+fn storage_percent_used() {
+    let storage = sample_provider().storage_info().unwrap();
+    assert_eq!(storage.len(), 1);
+    assert_eq!(storage[0].used_bytes, 375_000_000_000);
+    assert_eq!(storage[0].percent_used, 75.0);
+
+    let display = StorageDisplay::from(&storage[0]);
+    assert_eq!(display.total_space, "500.00 GB");
+    assert_eq!(display.used_space, "375.00 GB");
+    assert_eq!(display.percent_used, "75.00 %");
+    assert_eq!(display.type_, "SSD");
+}
+
+#[test]
+fn empty_disk_is_zero_percent_used() {
+    let mut provider = FakeProvider::new().with_disks(vec![DiskReading::default()]);
+    let storage = provider.storage_info().unwrap();
+    assert_eq!(storage[0].percent_used, 0.0);
+}
+
+#[test]
+fn no_disks_is_an_empty_list() {
+    let storage = FakeProvider::new().storage_info().unwrap();
+    assert!(storage.is_empty());
+}
+
+#[test]
+fn network_rates_use_the_elapsed_time() {
+    let networks = sample_provider().network_info().unwrap();
+    // Sorted by name
+    assert_eq!(networks[0].name.as_deref(), Some("eth0"));
+    assert_eq!(networks[1].name.as_deref(), Some("wlan0"));
+    // No time elapsed means no rate rather than a division by zero
+    assert_eq!(networks[0].rx_bytes_per_sec, 0.0);
+    assert_eq!(networks[1].rx_bytes_per_sec, 1_500_000.0);
+    assert_eq!(networks[1].tx_bytes_per_sec, 500.0);
+
+    let display = NetworkDisplay::from(&networks[1]);
+    assert_eq!(display.rx_rate, "1.50 MB/s");
+    assert_eq!(display.tx_rate, "500.00 B/s");
+    assert_eq!(display.link_state, "unknown");
+}
+
+#[test]
+fn cli_reports_the_failing_section() {
+    let options = CliOptions::parse(["--cpu"]).unwrap().unwrap();
+    let error = cli::collect_from(&mut Collector::with_provider(FakeProvider::new()), &options)
+        .unwrap_err();
+    assert_eq!(error.to_string(), "No data: no CPUs reported");
+}
+
+#[test]
+fn io_errors_keep_their_kind() {
+    let denied = io::Error::new(io::ErrorKind::PermissionDenied, "/proc/1/environ");
+    assert!(matches!(
+        MachineInfoError::from(denied),
+        MachineInfoError::PermissionDenied(_)
+    ));
+    let unsupported = io::Error::new(io::ErrorKind::Unsupported, "signals");
+    assert!(matches!(
+        MachineInfoError::from(unsupported),
+        MachineInfoError::Unsupported(_)
+    ));
+    let other = io::Error::other("broken pipe");
+    assert!(matches!(
+        MachineInfoError::from(other),
+        MachineInfoError::Io(_)
+    ));
+
+    // Window settings read from a database without the table stay database errors
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    assert!(matches!(
+        WindowInformation::load_from_db(&conn),
+        Err(MachineInfoError::Database(_))
+    ));
+}
+
+#[test]
+fn cli_report_only_contains_requested_sections() {
+    let options = CliOptions::parse(["--memory", "--storage"])
+        .unwrap()
+        .unwrap();
+    let report =
+        cli::collect_from(&mut Collector::with_provider(sample_provider()), &options).unwrap();
+    assert!(report.cpu.is_none());
+    assert!(report.network.is_none());
+
+    let text = cli::render_text(&report);
+    assert!(text.contains("Used Memory:  4.00 GB"));
+    assert!(text.contains("Percent Used:  75.00 %"));
+    assert!(!text.contains("CPU Information"));
+
+    #[cfg(feature = "serde")]
+    {
+        let json = cli::render_json(&report);
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["memory"]["total_bytes"], 8_589_934_592u64);
+        assert_eq!(json["storage"][0]["percent_used"], 75.0);
+        assert!(json.get("cpu").is_none());
+    }
+}
+
+#[test]
+fn collector_only_reads_requested_subsystems() {
+    let provider = sample_provider().with_processes(vec![ProcessInfo {
+        pid: 42,
+        name: Some("init".to_string()),
+        ..Default::default()
+    }]);
+    let mut collector = Collector::with_provider(provider);
+
+    let (snapshot, errors) = collector.refresh(Subsystems {
+        memory: true,
+        processes: true,
+        ..Default::default()
+    });
+    assert!(errors.is_empty());
+    assert!(snapshot.timestamp > 0);
+    assert!(snapshot.cpu.is_none());
+    assert!(snapshot.storage.is_none());
+    assert!(snapshot.network.is_none());
+    assert_eq!(snapshot.memory.unwrap().used_bytes, 4 * GIB);
+    assert_eq!(snapshot.processes.unwrap()[0].pid, 42);
+}
+
+#[test]
+fn collector_keeps_working_subsystems_when_one_fails() {
+    let mut provider = sample_provider();
+    provider.cpus.clear();
+    let mut collector = Collector::with_provider(provider);
+
+    let (snapshot, errors) = collector.refresh(Subsystems::all());
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], MachineInfoError::NoData(_)));
+    assert!(snapshot.cpu.is_none());
+    assert!(snapshot.memory.is_some());
+    assert_eq!(snapshot.storage.unwrap().len(), 1);
+    assert_eq!(snapshot.network.unwrap().len(), 2);
+}
+
+#[test]
+fn fake_provider_cannot_send_signals() {
+    let mut collector = Collector::with_provider(sample_provider());
+    let target = ProcessInfo {
+        pid: 1,
+        ..Default::default()
+    };
+    let result = collector.send_signal(&target, ProcessSignal::Terminate);
+    assert!(matches!(result, Err(MachineInfoError::Unsupported(_))));
+}
+
+#[test]
+fn reused_pids_are_not_the_same_process() {
+    let shown = ProcessInfo {
+        pid: 4242,
+        name: Some("backup".to_string()),
+        start_time: 1_735_787_045,
+        ..Default::default()
+    };
+    assert!(shown.is_same_process("backup", 1_735_787_045));
+    // The pid was handed to a new process since the table was filled
+    assert!(!shown.is_same_process("backup", 1_735_787_100));
+    assert!(!shown.is_same_process("sshd", 1_735_787_045));
+}
+
+#[test]
+fn signals_to_exited_processes_fail() {
+    let mut provider = SysinfoProvider::new();
+    let exited = ProcessInfo {
+        pid: u32::MAX - 1,
+        name: Some("gone".to_string()),
+        ..Default::default()
+    };
+    let result = provider.send_signal(&exited, ProcessSignal::Kill);
+    assert!(matches!(result, Err(MachineInfoError::NoData(_))));
+}
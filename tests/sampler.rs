@@ -0,0 +1,100 @@
+use machine_info::sampler::{Sampler, SamplerEvent};
+use machine_info::sys::collector::Collector;
+use machine_info::sys::fake::FakeProvider;
+use machine_info::sys::provider::{CpuReading, MemoryReading};
+use machine_info::{MachineInfoError, ProcessInfo, ProcessSignal, Subsystems};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn sample_collector() -> Collector<FakeProvider> {
+    Collector::with_provider(
+        FakeProvider::new()
+            .with_cpus(vec![CpuReading {
+                brand: "Test CPU".to_string(),
+                usage_percent: 50.0,
+                ..Default::default()
+            }])
+            .with_memory(MemoryReading {
+                total_bytes: 1024,
+                available_bytes: 256,
+                ..Default::default()
+            }),
+    )
+}
+
+fn next_snapshot(sampler: &Sampler) -> machine_info::Snapshot {
+    loop {
+        match sampler.recv_timeout(TIMEOUT) {
+            Some(SamplerEvent::Snapshot(snapshot)) => return *snapshot,
+            Some(_) => continue,
+            None => panic!("no snapshot within {:?}", TIMEOUT),
+        }
+    }
+}
+
+#[test]
+fn sampler_delivers_snapshots_and_notifies() {
+    let notified = Arc::new(AtomicUsize::new(0));
+    let sampler = Sampler::builder(sample_collector())
+        .with_interval(Duration::from_secs(60))
+        .with_subsystems(Subsystems {
+            cpu: true,
+            ..Default::default()
+        })
+        .spawn({
+            let notified = Arc::clone(&notified);
+            move || {
+                notified.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+    // The first snapshot is taken right away, not after the interval
+    let snapshot = next_snapshot(&sampler);
+    assert_eq!(snapshot.cpu.unwrap().usage_percent, 50.0);
+    assert!(snapshot.memory.is_none());
+    // notify runs right after the event is queued, so it may lag behind recv
+    let started = Instant::now();
+    while notified.load(Ordering::SeqCst) == 0 {
+        assert!(started.elapsed() < TIMEOUT, "notify was never called");
+        thread::yield_now();
+    }
+
+    // Changing the subsystems triggers another snapshot without waiting a minute
+    sampler.set_subsystems(Subsystems {
+        memory: true,
+        ..Default::default()
+    });
+    let snapshot = next_snapshot(&sampler);
+    assert!(snapshot.cpu.is_none());
+    assert_eq!(snapshot.memory.unwrap().used_bytes, 768);
+}
+
+#[test]
+fn sampler_reports_signal_results() {
+    let sampler = Sampler::builder(sample_collector())
+        .with_interval(Duration::from_secs(60))
+        .spawn(|| {});
+    next_snapshot(&sampler);
+
+    let target = ProcessInfo {
+        pid: 1,
+        ..Default::default()
+    };
+    sampler.send_signal(target, ProcessSignal::Terminate);
+    match sampler.recv_timeout(TIMEOUT) {
+        Some(SamplerEvent::SignalSent {
+            pid,
+            signal,
+            result,
+        }) => {
+            assert_eq!(pid, 1);
+            assert_eq!(signal, ProcessSignal::Terminate);
+            assert!(matches!(result, Err(MachineInfoError::Unsupported(_))));
+        }
+        other => panic!("expected a signal result, got {:?}", other),
+    }
+}
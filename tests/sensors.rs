@@ -0,0 +1,307 @@
+use machine_info::cli;
+use machine_info::db::history::RESOLUTION_RAW;
+use machine_info::display::TemperatureDisplay;
+use machine_info::exporter::render_metrics;
+use machine_info::sys::collector::Collector;
+use machine_info::sys::fake::FakeProvider;
+use machine_info::sys::sensors::read_hwmon;
+use machine_info::{
+    Fan, HistoryRecorder, HistorySettings, Memory, Processor, Sensors, Snapshot, Subsystems,
+    Temperature,
+};
+use rusqlite::Connection;
+use std::fs;
+use std::path::Path;
+
+// Writes a chip directory the way the kernel lays out /sys/class/hwmon/hwmonN
+fn chip(root: &Path, dir: &str, attributes: &[(&str, &str)]) {
+    let dir = root.join(dir);
+    fs::create_dir_all(&dir).unwrap();
+    for (name, value) in attributes {
+        fs::write(dir.join(name), format!("{}\n", value)).unwrap();
+    }
+}
+
+fn package(current: f32) -> Temperature {
+    Temperature {
+        label: "coretemp Package id 0".to_string(),
+        chip: "hwmon2".to_string(),
+        current_celsius: current,
+        max_celsius: Some(80.0),
+        critical_celsius: Some(100.0),
+    }
+}
+
+#[test]
+fn hwmon_tree_is_read_in_order() {
+    let root = std::env::temp_dir().join(format!("machine_info_hwmon_{}", std::process::id()));
+    chip(
+        &root,
+        "hwmon0",
+        &[
+            ("name", "acpitz"),
+            ("temp1_input", "27800"),
+            ("temp1_crit", "0"),
+        ],
+    );
+    chip(
+        &root,
+        "hwmon2",
+        &[
+            ("name", "coretemp"),
+            ("temp1_input", "54000"),
+            ("temp1_label", "Package id 0"),
+            ("temp1_max", "80000"),
+            ("temp1_crit", "100000"),
+            ("temp10_input", "51000"),
+            ("temp10_label", "Core 8"),
+            ("temp2_input", "52000"),
+            ("temp2_label", "Core 0"),
+        ],
+    );
+    chip(
+        &root,
+        "hwmon10",
+        &[
+            ("name", "thinkpad"),
+            ("fan1_input", "2400"),
+            ("fan2_input", "0"),
+            ("fan3_label", "no reading"),
+        ],
+    );
+    // Older drivers keep their attributes one level down
+    chip(
+        &root,
+        "hwmon3/device",
+        &[
+            ("name", "nvme"),
+            ("temp1_input", "38850"),
+            ("temp1_label", "Composite"),
+        ],
+    );
+    // Chips without a name are skipped
+    chip(&root, "hwmon4", &[("temp1_input", "1000")]);
+
+    let sensors = read_hwmon(&root).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+
+    let labels: Vec<&str> = sensors
+        .temperatures
+        .iter()
+        .map(|t| t.label.as_str())
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            "acpitz temp1",
+            "coretemp Package id 0",
+            "coretemp Core 0",
+            "coretemp Core 8",
+            "nvme Composite",
+        ]
+    );
+    assert_eq!(
+        sensors.temperatures[0],
+        Temperature {
+            label: "acpitz temp1".to_string(),
+            chip: "hwmon0".to_string(),
+            current_celsius: 27.8,
+            max_celsius: None,
+            critical_celsius: None,
+        }
+    );
+    assert_eq!(sensors.temperatures[1], package(54.0));
+    assert_eq!(sensors.temperatures[4].chip, "hwmon3");
+    assert_eq!(
+        sensors.fans,
+        [
+            Fan {
+                label: "thinkpad fan1".to_string(),
+                chip: "hwmon10".to_string(),
+                rpm: 2400,
+            },
+            Fan {
+                label: "thinkpad fan2".to_string(),
+                chip: "hwmon10".to_string(),
+                rpm: 0,
+            },
+        ]
+    );
+
+    // No hwmon at all is an empty reading, not an error
+    assert_eq!(read_hwmon(&root).unwrap(), Sensors::default());
+}
+
+#[test]
+fn throttling_sensors_are_flagged() {
+    let display = TemperatureDisplay::from(&package(54.0));
+    assert_eq!(
+        display.summary(),
+        "54.0 °C (max 80.0 °C, critical 100.0 °C)"
+    );
+    assert!(!display.throttling);
+
+    let display = TemperatureDisplay::from(&package(80.0));
+    assert!(display.throttling);
+    assert_eq!(
+        display.summary(),
+        "80.0 °C (max 80.0 °C, critical 100.0 °C) throttling"
+    );
+
+    let sensors = Sensors {
+        temperatures: vec![package(61.0), package(72.5), package(40.0)],
+        fans: Vec::new(),
+    };
+    assert_eq!(sensors.hottest().unwrap().current_celsius, 72.5);
+}
+
+#[test]
+fn sensors_are_part_of_the_cpu_report() {
+    let sensors = Sensors {
+        temperatures: vec![package(54.0)],
+        fans: vec![Fan {
+            label: "thinkpad fan1".to_string(),
+            chip: "hwmon10".to_string(),
+            rpm: 2400,
+        }],
+    };
+    let mut collector = Collector::with_provider(FakeProvider::new().with_sensors(sensors.clone()));
+    let (snapshot, errors) = collector.refresh(Subsystems {
+        sensors: true,
+        ..Default::default()
+    });
+    assert!(errors.is_empty());
+    assert_eq!(snapshot.sensors.as_ref(), Some(&sensors));
+
+    let report = Snapshot {
+        sensors: Some(sensors),
+        ..Default::default()
+    };
+    assert_eq!(
+        cli::render_text(&report),
+        "Sensors\n  \
+         coretemp Package id 0: 54.0 °C (max 80.0 °C, critical 100.0 °C)\n  \
+         thinkpad fan1:         2400 RPM\n\n"
+    );
+    #[cfg(feature = "serde")]
+    {
+        let json: serde_json::Value = serde_json::from_str(&cli::render_json(&report)).unwrap();
+        assert_eq!(
+            json["sensors"],
+            serde_json::json!({
+                "temperatures": [{
+                    "label": "coretemp Package id 0",
+                    "chip": "hwmon2",
+                    "current_celsius": 54.0,
+                    "max_celsius": 80.0,
+                    "critical_celsius": 100.0
+                }],
+                "fans": [{"label": "thinkpad fan1", "chip": "hwmon10", "rpm": 2400}]
+            })
+        );
+    }
+}
+
+#[test]
+fn sockets_with_the_same_sensor_export_separate_series() {
+    let snapshot = Snapshot {
+        sensors: Some(Sensors {
+            temperatures: vec![
+                package(54.0),
+                Temperature {
+                    chip: "hwmon3".to_string(),
+                    ..package(48.0)
+                },
+            ],
+            fans: vec![Fan {
+                label: "thinkpad fan1".to_string(),
+                chip: "hwmon10".to_string(),
+                rpm: 2400,
+            }],
+        }),
+        ..Default::default()
+    };
+    let metrics = render_metrics(
+        &snapshot,
+        Subsystems {
+            sensors: true,
+            ..Default::default()
+        },
+    );
+    assert!(metrics.contains(
+        "machineinfo_sensor_temperature_celsius{sensor=\"coretemp Package id 0\",chip=\"hwmon2\"} 54\n"
+    ));
+    assert!(metrics.contains(
+        "machineinfo_sensor_temperature_celsius{sensor=\"coretemp Package id 0\",chip=\"hwmon3\"} 48\n"
+    ));
+    assert!(
+        metrics
+            .contains("machineinfo_fan_speed_rpm{fan=\"thinkpad fan1\",chip=\"hwmon10\"} 2400\n")
+    );
+}
+
+#[test]
+fn temperatures_are_recorded_and_compacted() {
+    let conn = Connection::open_in_memory().unwrap();
+    let recorder = HistoryRecorder::new(HistorySettings {
+        raw_retention_secs: 60,
+        ..Default::default()
+    });
+    HistoryRecorder::create_tables(&conn).unwrap();
+    for (timestamp, current) in [(0, 50.0), (20, 70.0), (40, 60.0), (120, 55.0)] {
+        // The second socket reports the same label from another hwmon directory
+        let second_socket = Temperature {
+            chip: "hwmon3".to_string(),
+            ..package(current - 20.0)
+        };
+        recorder
+            .record_at(
+                &conn,
+                timestamp,
+                &Processor::default(),
+                &Memory::default(),
+                &[],
+                &[package(current), second_socket],
+            )
+            .unwrap();
+    }
+    recorder.compact(&conn, 130).unwrap();
+
+    let samples = HistoryRecorder::sensor_history(&conn, 0, 200).unwrap();
+    let rows: Vec<(i64, u32, &str, &str, f32, f32)> = samples
+        .iter()
+        .map(|s| {
+            (
+                s.timestamp,
+                s.resolution,
+                s.sensor.as_str(),
+                s.chip.as_str(),
+                s.temperature_avg,
+                s.temperature_max,
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (0, 60, "coretemp Package id 0", "hwmon2", 60.0, 70.0),
+            (0, 60, "coretemp Package id 0", "hwmon3", 40.0, 50.0),
+            (
+                120,
+                RESOLUTION_RAW,
+                "coretemp Package id 0",
+                "hwmon2",
+                55.0,
+                55.0
+            ),
+            (
+                120,
+                RESOLUTION_RAW,
+                "coretemp Package id 0",
+                "hwmon3",
+                35.0,
+                35.0
+            ),
+        ]
+    );
+}
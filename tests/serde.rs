@@ -0,0 +1,93 @@
+#![cfg(feature = "serde")]
+
+use machine_info::{
+    HistoryRecorder, HistorySettings, Memory, Processor, ProcessorCore, Storage, WindowInformation,
+};
+
+fn sample_processor() -> Processor {
+    Processor {
+        name: Some("Test CPU".to_string()),
+        vendor: Some("GenuineIntel".to_string()),
+        architecture: Some("x86_64".to_string()),
+        frequency_mhz: 3200,
+        cores: 2,
+        usage_percent: 12.5,
+        per_core: vec![
+            ProcessorCore {
+                index: 0,
+                usage_percent: 20.0,
+                frequency_mhz: 3200,
+                times: None,
+            },
+            ProcessorCore {
+                index: 1,
+                usage_percent: 5.0,
+                frequency_mhz: 3100,
+                times: None,
+            },
+        ],
+        topology: None,
+        features: None,
+        load: None,
+        times: None,
+    }
+}
+
+fn sample_storage() -> Storage {
+    Storage {
+        name: Some("/dev/nvme0n1p2".to_string()),
+        mount_point: Some("/".to_string()),
+        file_system: Some("ext4".to_string()),
+        type_: Some("SSD".to_string()),
+        is_removable: false,
+        total_bytes: 500_000_000_000,
+        free_bytes: 120_200_000_000,
+        used_bytes: 379_800_000_000,
+        percent_used: 75.96,
+    }
+}
+
+#[test]
+fn processor_round_trips_through_json() {
+    let cpu = sample_processor();
+    let json = serde_json::to_string(&cpu).unwrap();
+    let back: Processor = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, cpu);
+}
+
+#[test]
+fn storage_round_trips_through_toml_with_type_key() {
+    let disk = sample_storage();
+    let text = toml::to_string(&disk).unwrap();
+    assert!(text.contains("type = \"SSD\""));
+    let back: Storage = toml::from_str(&text).unwrap();
+    assert_eq!(back, disk);
+}
+
+#[test]
+fn missing_fields_fall_back_to_defaults() {
+    let memory: Memory = serde_json::from_str(r#"{"total_bytes": 1024}"#).unwrap();
+    assert_eq!(memory.total_bytes, 1024);
+    assert_eq!(memory.used_bytes, 0);
+
+    let window: WindowInformation =
+        serde_json::from_str(r#"{"width": 800, "maximized": true}"#).unwrap();
+    assert_eq!(window.width, 800);
+    assert!(window.maximized);
+}
+
+#[test]
+fn recorder_state_is_not_serialized() {
+    let mut recorder = HistoryRecorder::new(HistorySettings {
+        sample_interval_secs: 10,
+        ..Default::default()
+    });
+    recorder.last_sample = Some(1_735_787_045);
+    recorder.last_compaction = Some(1_735_787_000);
+
+    let json = serde_json::to_string(&recorder).unwrap();
+    assert!(!json.contains("last_sample"));
+    assert!(!json.contains("last_compaction"));
+    let back: HistoryRecorder = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, HistoryRecorder::new(recorder.settings.clone()));
+}
@@ -0,0 +1,169 @@
+use machine_info::cli::{self, CliOptions};
+use machine_info::display::{SystemDisplay, format_duration};
+use machine_info::sys::collector::Collector;
+use machine_info::sys::fake::FakeProvider;
+use machine_info::sys::system::{host_architecture, parse_utmp};
+use machine_info::{LoggedInUser, Snapshot, Subsystems, SystemInfo};
+use sysinfo::System;
+
+const RECORD_SIZE: usize = 384;
+
+// One utmp record in the Linux layout
+fn utmp_record(kind: i16, line: &str, user: &str, host: &str, login_time: i32) -> Vec<u8> {
+    let mut record = vec![0u8; RECORD_SIZE];
+    record[0..2].copy_from_slice(&kind.to_ne_bytes());
+    record[8..8 + line.len()].copy_from_slice(line.as_bytes());
+    record[44..44 + user.len()].copy_from_slice(user.as_bytes());
+    record[76..76 + host.len()].copy_from_slice(host.as_bytes());
+    record[340..344].copy_from_slice(&login_time.to_ne_bytes());
+    record
+}
+
+fn sample_system() -> SystemInfo {
+    SystemInfo {
+        hostname: Some("build-01".to_string()),
+        os_name: Some("Ubuntu".to_string()),
+        os_version: Some("Linux 24.04 Ubuntu".to_string()),
+        kernel_version: Some("6.8.0-45-generic".to_string()),
+        distribution_id: Some("ubuntu".to_string()),
+        architecture: Some("x86_64".to_string()),
+        emulated_architecture: None,
+        uptime_secs: 3 * 86_400 + 4 * 3600 + 5 * 60 + 6,
+        boot_time: 1_735_787_045,
+        users: vec![LoggedInUser {
+            name: "alice".to_string(),
+            terminal: "pts/0".to_string(),
+            host: Some("10.0.0.5".to_string()),
+            login_time: 1_735_787_045,
+        }],
+    }
+}
+
+#[test]
+fn utmp_keeps_only_user_sessions() {
+    let mut bytes = Vec::new();
+    bytes.extend(utmp_record(2, "~", "reboot", "6.8.0", 1_000)); // BOOT_TIME
+    bytes.extend(utmp_record(7, "tty1", "alice", "", 1_735_787_045));
+    bytes.extend(utmp_record(8, "pts/1", "", "", 1_700_000_000)); // DEAD_PROCESS
+    bytes.extend(utmp_record(
+        7,
+        "pts/0",
+        "bob",
+        "192.168.1.20",
+        1_735_790_000,
+    ));
+    // A record that is still being written is ignored
+    bytes.extend(&utmp_record(7, "pts/2", "carol", "", 1)[..100]);
+
+    let users = parse_utmp(&bytes);
+    assert_eq!(
+        users,
+        [
+            LoggedInUser {
+                name: "alice".to_string(),
+                terminal: "tty1".to_string(),
+                host: None,
+                login_time: 1_735_787_045,
+            },
+            LoggedInUser {
+                name: "bob".to_string(),
+                terminal: "pts/0".to_string(),
+                host: Some("192.168.1.20".to_string()),
+                login_time: 1_735_790_000,
+            },
+        ]
+    );
+
+    // Fields that fill their whole width have no terminating NUL
+    let name = "a".repeat(32);
+    let users = parse_utmp(&utmp_record(7, "tty2", &name, "", 0));
+    assert_eq!(users[0].name, name);
+    assert_eq!(users[0].terminal, "tty2");
+}
+
+#[test]
+fn uptime_is_shown_in_days_and_clock_time() {
+    assert_eq!(format_duration(59), "00:00:59");
+    assert_eq!(format_duration(86_399), "23:59:59");
+    assert_eq!(format_duration(86_400 + 61), "1 day, 00:01:01");
+
+    let display = SystemDisplay::from(&sample_system());
+    assert_eq!(display.uptime, "3 days, 04:05:06");
+    assert_eq!(display.boot_time, "2025-01-02 03:04:05 UTC");
+    assert_eq!(display.users[0].host, "10.0.0.5");
+    assert_eq!(
+        sample_system().identity().architecture.as_deref(),
+        Some("x86_64")
+    );
+}
+
+#[test]
+fn system_is_read_only_when_requested() {
+    let provider = FakeProvider::new().with_system(sample_system());
+    let mut collector = Collector::with_provider(provider);
+
+    let (snapshot, errors) = collector.refresh(Subsystems {
+        system: true,
+        ..Default::default()
+    });
+    assert!(errors.is_empty());
+    assert_eq!(snapshot.system, Some(sample_system()));
+
+    let (snapshot, _) = collector.refresh(Subsystems {
+        memory: true,
+        ..Default::default()
+    });
+    assert!(snapshot.system.is_none());
+}
+
+#[test]
+fn cli_prints_the_system_section() {
+    let options = CliOptions::parse(["--system"]).unwrap().unwrap();
+    assert!(options.system && !options.cpu);
+    assert!(CliOptions::parse(["--json"]).unwrap().unwrap().system);
+
+    let mut collector = Collector::with_provider(FakeProvider::new().with_system(sample_system()));
+    let report = cli::collect_from(&mut collector, &options).unwrap();
+
+    let text = cli::render_text(&report);
+    assert!(text.starts_with("System Information\n  Hostname:     build-01\n"));
+    assert!(text.contains("  Uptime:       3 days, 04:05:06\n"));
+    assert!(
+        text.contains(
+            "  User:         alice on pts/0 from 10.0.0.5 since 2025-01-02 03:04:05 UTC\n"
+        )
+    );
+
+    #[cfg(feature = "serde")]
+    {
+        let json = cli::render_json(&report);
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["system"]["hostname"], "build-01");
+        assert_eq!(json["system"]["users"][0]["name"], "alice");
+        assert_eq!(json["system"]["users"][0]["terminal"], "pts/0");
+    }
+}
+
+#[test]
+fn translated_processes_show_the_host_architecture() {
+    let system = SystemInfo {
+        architecture: Some("arm64".to_string()),
+        emulated_architecture: Some("x86_64".to_string()),
+        ..sample_system()
+    };
+    assert_eq!(
+        SystemDisplay::from(&system).architecture,
+        "arm64 (running as x86_64)"
+    );
+    assert_eq!(SystemDisplay::from(&sample_system()).architecture, "x86_64");
+    let report = Snapshot {
+        system: Some(system),
+        ..Default::default()
+    };
+    assert!(cli::render_text(&report).contains("  Architecture: arm64 (running as x86_64)\n"));
+
+    // Linux can't tell, so the host is whatever uname says
+    if cfg!(target_os = "linux") {
+        assert_eq!(host_architecture(), Some(System::cpu_arch()));
+    }
+}
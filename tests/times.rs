@@ -0,0 +1,154 @@
+#[cfg(feature = "serde")]
+use machine_info::MachineIdentity;
+use machine_info::cli;
+use machine_info::display::TimesDisplay;
+#[cfg(feature = "serde")]
+use machine_info::export::{parse_profile, render_json_export};
+use machine_info::exporter::render_metrics;
+use machine_info::sys::collector::Collector;
+use machine_info::sys::fake::FakeProvider;
+use machine_info::sys::provider::CpuReading;
+use machine_info::sys::times::CpuTimeCounters;
+use machine_info::{CpuTimes, Processor, ProcessorCore, Subsystems};
+
+// Two CPUs, guest columns at the end of each line
+const STAT: &str = "cpu  2000 100 600 7000 200 50 50 0 300 0\n\
+                    cpu0 1000 50 300 3500 100 25 25 0 150 0\n\
+                    cpu1 1000 50 300 3500 100 25 25 0 150 0\n\
+                    intr 199292724 9 0 0\n\
+                    ctxt 1990473000\n";
+
+// Ticks per state: user, nice, system, idle, iowait, irq, softirq, steal
+fn counters(total: [u64; 8], per_cpu: &[(u32, [u64; 8])]) -> CpuTimeCounters {
+    CpuTimeCounters {
+        total,
+        per_cpu: per_cpu.to_vec(),
+    }
+}
+
+fn two_cores() -> Processor {
+    Processor {
+        per_core: (0..2)
+            .map(|index| ProcessorCore {
+                index,
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn stat_cpu_lines_are_parsed() {
+    let parsed = CpuTimeCounters::parse(STAT).unwrap();
+    // Guest time is already in user and nice, so it is not read
+    assert_eq!(parsed.total, [2000, 100, 600, 7000, 200, 50, 50, 0]);
+    assert_eq!(parsed.per_cpu.len(), 2);
+    assert_eq!(
+        parsed.per_cpu[1],
+        (1, [1000, 50, 300, 3500, 100, 25, 25, 0])
+    );
+
+    // Old kernels without the steal column
+    let parsed = CpuTimeCounters::parse("cpu  1 2 3 4 5 6 7\n").unwrap();
+    assert_eq!(parsed.total, [1, 2, 3, 4, 5, 6, 7, 0]);
+
+    assert!(CpuTimeCounters::parse("intr 1 2 3\n").is_none());
+    assert!(CpuTimeCounters::parse("cpu  1 x 3 4\n").is_none());
+}
+
+#[test]
+fn percentages_come_from_two_readings() {
+    let mut last = None;
+    let mut cpu = two_cores();
+    let start = [1000, 0, 1000, 1000, 0, 0, 0, 0];
+    let doubled = [2000, 0, 2000, 2000, 0, 0, 0, 0];
+    cpu.update_times(
+        &mut last,
+        Some(counters(doubled, &[(0, start), (1, start)])),
+    );
+    // The first reading is only a baseline
+    assert_eq!(cpu.times, None);
+
+    // cpu0 is waiting on a disk, cpu1 loses most of its time to the hypervisor
+    let stalled = [1020, 0, 1010, 1020, 50, 0, 0, 0];
+    let stolen = [1010, 0, 1000, 1010, 0, 0, 0, 80];
+    let total = [2030, 0, 2010, 2030, 50, 0, 0, 80];
+    cpu.update_times(
+        &mut last,
+        Some(counters(total, &[(0, stalled), (1, stolen)])),
+    );
+    let core0 = cpu.per_core[0].times.clone().unwrap();
+    assert_eq!(core0.iowait_percent, 50.0);
+    assert_eq!(core0.user_percent, 20.0);
+    assert_eq!(cpu.per_core[1].times.clone().unwrap().steal_percent, 80.0);
+    let times = cpu.times.clone().unwrap();
+    assert_eq!(times.iowait_percent, 25.0);
+    assert_eq!(times.steal_percent, 40.0);
+
+    // No tick passed, and cpu1 went offline
+    cpu.update_times(&mut last, Some(counters(total, &[(0, stalled)])));
+    assert_eq!(cpu.times, None);
+    assert_eq!(cpu.per_core[0].times, None);
+}
+
+#[test]
+fn breakdown_is_shown_like_top() {
+    let times = CpuTimes {
+        user_percent: 12.0,
+        nice_percent: 0.5,
+        system_percent: 3.12,
+        idle_percent: 80.16,
+        iowait_percent: 4.0,
+        irq_percent: 0.0,
+        softirq_percent: 0.2,
+        steal_percent: 0.0,
+    };
+    let display = TimesDisplay::from(&times);
+    assert_eq!(
+        display.summary(),
+        "12.0 us, 3.1 sy, 0.5 ni, 80.2 id, 4.0 wa, 0.0 hi, 0.2 si, 0.0 st"
+    );
+    assert_eq!(display.stalls, "wa 4.0 st 0.0");
+}
+
+#[test]
+fn breakdown_is_part_of_the_cpu_report() {
+    let before = [0, 0, 0, 0, 0, 0, 0, 0];
+    let provider = FakeProvider::new()
+        .with_cpus(vec![CpuReading::default(); 2])
+        .with_times(counters(before, &[(0, before), (1, before)]));
+    let mut collector = Collector::with_provider(provider);
+    let after = [50, 0, 0, 0, 50, 0, 0, 0];
+    collector.provider_mut().times = Some(counters(
+        [100, 0, 0, 0, 100, 0, 0, 0],
+        &[(0, after), (1, after)],
+    ));
+    let requested = Subsystems {
+        cpu: true,
+        ..Default::default()
+    };
+    let (snapshot, errors) = collector.refresh(requested);
+    assert!(errors.is_empty());
+    let cpu = snapshot.cpu.as_ref().unwrap();
+    assert_eq!(cpu.times.as_ref().unwrap().iowait_percent, 50.0);
+
+    let text = cli::render_text(&snapshot);
+    assert!(text.contains(
+        "  CPU Time:       50.0 us, 0.0 sy, 0.0 ni, 0.0 id, 50.0 wa, 0.0 hi, 0.0 si, 0.0 st\n"
+    ));
+    assert!(text.contains("  CPU 1            0.00 %  0.00 GHz  50.0 us, 0.0 sy,"));
+
+    let metrics = render_metrics(&snapshot, requested);
+    assert!(metrics.contains("machineinfo_cpu_time_ratio{mode=\"iowait\"} 0.5\n"));
+    assert!(metrics.contains("machineinfo_cpu_core_time_ratio{core=\"1\",mode=\"user\"} 0.5\n"));
+
+    #[cfg(feature = "serde")]
+    {
+        let json = render_json_export(&MachineIdentity::default(), &snapshot);
+        let profile = parse_profile(&json).unwrap();
+        let cpu = profile.snapshot.cpu.unwrap();
+        assert_eq!(cpu.times, snapshot.cpu.as_ref().unwrap().times);
+        assert_eq!(cpu.per_core[1].times.as_ref().unwrap().user_percent, 50.0);
+    }
+}
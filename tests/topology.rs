@@ -0,0 +1,176 @@
+#[cfg(feature = "serde")]
+use machine_info::MachineIdentity;
+use machine_info::display::{TopologyDisplay, format_cache_size, format_cpu_list};
+#[cfg(feature = "serde")]
+use machine_info::export::{parse_profile, render_json_export};
+use machine_info::sys::collector::Collector;
+use machine_info::sys::fake::FakeProvider;
+use machine_info::sys::provider::CpuReading;
+use machine_info::sys::topology::{parse_cpu_list, read_topology};
+use machine_info::{CpuCache, CpuTopology, NumaNode, Subsystems, cli};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn write(root: &Path, path: &str, value: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, format!("{}\n", value)).unwrap();
+}
+
+fn temp_root(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("machine_info_{}_{}", name, std::process::id()))
+}
+
+fn cache(level: u32, kind: &str, size_bytes: u64, instances: usize) -> CpuCache {
+    CpuCache {
+        level,
+        kind: kind.to_string(),
+        size_bytes,
+        instances,
+    }
+}
+
+// One socket, two cores with two threads each, like /sys/devices/system on a
+// small SMT laptop. cpu4 was taken offline.
+fn smt_tree(root: &Path) {
+    for cpu in 0..4 {
+        let core = cpu % 2;
+        let siblings = if core == 0 { "0,2" } else { "1,3" };
+        let dir = format!("cpu/cpu{}", cpu);
+        write(root, &format!("{}/topology/physical_package_id", dir), "0");
+        write(
+            root,
+            &format!("{}/topology/core_id", dir),
+            &core.to_string(),
+        );
+        for (index, level, kind, size, shared) in [
+            (0, "1", "Data", "48K", siblings),
+            (1, "1", "Instruction", "32K", siblings),
+            (2, "2", "Unified", "1280K", siblings),
+            (3, "3", "Unified", "12288K", "0-3"),
+        ] {
+            let cache = format!("{}/cache/index{}", dir, index);
+            write(root, &format!("{}/level", cache), level);
+            write(root, &format!("{}/type", cache), kind);
+            write(root, &format!("{}/size", cache), size);
+            write(root, &format!("{}/shared_cpu_list", cache), shared);
+        }
+    }
+    write(root, "cpu/cpu4/online", "0");
+    write(root, "cpu/online", "0-3");
+    write(root, "node/node0/cpulist", "0-3");
+}
+
+fn smt_topology() -> CpuTopology {
+    CpuTopology {
+        sockets: 1,
+        physical_cores: 2,
+        logical_cpus: 4,
+        threads_per_core: 2,
+        caches: vec![
+            cache(1, "Data", 48 * 1024, 2),
+            cache(1, "Instruction", 32 * 1024, 2),
+            cache(2, "Unified", 1280 * 1024, 2),
+            cache(3, "Unified", 12 * 1024 * 1024, 1),
+        ],
+        numa_nodes: vec![NumaNode {
+            id: 0,
+            cpus: vec![0, 1, 2, 3],
+        }],
+    }
+}
+
+#[test]
+fn sysfs_tree_gives_sockets_cores_caches_and_nodes() {
+    let root = temp_root("topology");
+    smt_tree(&root);
+    let topology = read_topology(&root, None);
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(topology.unwrap(), smt_topology());
+}
+
+#[test]
+fn cpuinfo_fills_in_when_sysfs_has_no_topology() {
+    // Two sockets with one core each and no SMT, as some hypervisors report it
+    let cpuinfo = "processor\t: 0\nphysical id\t: 0\ncore id\t\t: 0\n\n\
+                   processor\t: 1\nphysical id\t: 1\ncore id\t\t: 0\n\n";
+    let root = temp_root("topology_cpuinfo");
+    write(&root, "cpu/cpu0/online", "1");
+    write(&root, "cpu/cpu1/online", "1");
+    let topology = read_topology(&root, Some(cpuinfo)).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(topology.sockets, 2);
+    assert_eq!(topology.physical_cores, 2);
+    assert_eq!(topology.logical_cpus, 2);
+    assert_eq!(topology.threads_per_core, 1);
+    assert!(topology.caches.is_empty());
+
+    // Nothing to go on at all
+    assert!(read_topology(&temp_root("topology_missing"), None).is_err());
+}
+
+#[test]
+fn cpu_lists_and_cache_sizes_are_formatted() {
+    assert_eq!(parse_cpu_list("0-3,8,10-11\n"), [0, 1, 2, 3, 8, 10, 11]);
+    assert_eq!(parse_cpu_list(""), Vec::<u32>::new());
+    assert_eq!(format_cpu_list(&[0, 1, 2, 3, 8, 10, 11]), "0-3,8,10-11");
+
+    assert_eq!(format_cache_size(48 * 1024), "48 KB");
+    assert_eq!(format_cache_size(1280 * 1024), "1.25 MB");
+    assert_eq!(format_cache_size(32 * 1024 * 1024), "32 MB");
+
+    let display = TopologyDisplay::from(&smt_topology());
+    assert_eq!(
+        display.caches,
+        [
+            ("L1 Data".to_string(), "48 KB × 2".to_string()),
+            ("L1 Instruction".to_string(), "32 KB × 2".to_string()),
+            ("L2".to_string(), "1.25 MB × 2".to_string()),
+            ("L3".to_string(), "12 MB".to_string()),
+        ]
+    );
+    assert_eq!(
+        display.numa_nodes,
+        [("Node 0".to_string(), "0-3".to_string())]
+    );
+}
+
+#[test]
+fn topology_is_reported_and_survives_an_export() {
+    let provider = FakeProvider::new()
+        .with_cpus(vec![CpuReading::default(); 4])
+        .with_topology(smt_topology());
+    let mut collector = Collector::with_provider(provider);
+    let (snapshot, errors) = collector.refresh(Subsystems {
+        cpu: true,
+        ..Default::default()
+    });
+    assert!(errors.is_empty());
+    let cpu = snapshot.cpu.as_ref().unwrap();
+    assert_eq!(cpu.topology, Some(smt_topology()));
+
+    let text = cli::render_text(&snapshot);
+    assert!(text.contains("  Logical CPUs:   4\n"));
+    assert!(text.contains("  Physical Cores: 2\n  Threads/Core:   2\n"));
+    assert!(text.contains("  L1 Data:        48 KB × 2\n"));
+    assert!(text.contains("  Node 0:         CPUs 0-3\n"));
+
+    #[cfg(feature = "serde")]
+    {
+        let json = render_json_export(&MachineIdentity::default(), &snapshot);
+        let profile = parse_profile(&json).unwrap();
+        assert_eq!(profile.snapshot.cpu.unwrap().topology, Some(smt_topology()));
+    }
+
+    // Providers without topology still report the CPU
+    let mut collector =
+        Collector::with_provider(FakeProvider::new().with_cpus(vec![CpuReading::default()]));
+    let (snapshot, errors) = collector.refresh(Subsystems {
+        cpu: true,
+        ..Default::default()
+    });
+    assert!(errors.is_empty());
+    assert_eq!(snapshot.cpu.unwrap().topology, None);
+}